@@ -0,0 +1,84 @@
+use crate::output::{format_size, OutputFormat};
+use crate::{output, GlobalOpts};
+use clap::{Args, Subcommand};
+use std::error::Error;
+use wrum_lib::config;
+use wrum_lib::install::cache;
+use wrum_lib::install::cache::CacheEntry;
+
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    #[clap(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    ///list the cached downloads, most recently used first
+    Ls,
+    ///remove the least recently used downloads until the cache fits in the maximum size
+    Prune(PruneArgs),
+    ///remove every cached download that isn't in use
+    Clear,
+}
+
+#[derive(Debug, Args)]
+struct PruneArgs {
+    ///maximum size of the cache in bytes, or with a K, M, G or T suffix (e.g. 20G). Defaults to downloadCacheMaxSize of the config
+    #[clap(long, value_parser = parse_size)]
+    max_size: Option<u64>,
+}
+
+pub fn execute(args: CacheArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    match args.command {
+        CacheCommand::Ls => print_entries("cache", &cache::list_entries()?, global_opt.output)?,
+        CacheCommand::Prune(prune_args) => {
+            let max_size = match prune_args.max_size {
+                Some(max_size) => max_size,
+                None => config::get_config()?.download_cache_max_size,
+            };
+            print_removed(&cache::prune(max_size)?, global_opt.output)?;
+        }
+        CacheCommand::Clear => print_removed(&cache::clear()?, global_opt.output)?,
+    }
+    Ok(0)
+}
+
+fn print_removed(entries: &[CacheEntry], format: OutputFormat) -> Result<(), Box<dyn Error>> {
+    if format == OutputFormat::Text {
+        println!("Removed {} cached downloads, {} freed.", entries.len(), format_size(entries.iter().map(|entry| entry.size).sum()));
+        return Ok(());
+    }
+    print_entries("cacheRemoved", entries, format)
+}
+
+fn print_entries(kind: &str, entries: &[CacheEntry], format: OutputFormat) -> Result<(), Box<dyn Error>> {
+    match format {
+        OutputFormat::Json => output::print_json(kind, &entries)?,
+        OutputFormat::Tsv => {
+            for entry in entries {
+                output::print_tsv([entry.key.clone(), entry.file_name.clone(), entry.size.to_string(), entry.last_used.to_string(), entry.url.clone()]);
+            }
+        }
+        OutputFormat::Text => {
+            for entry in entries {
+                println!("{} {} {}", entry.file_name, format_size(entry.size), entry.url);
+            }
+            println!("Total: {}", format_size(entries.iter().map(|entry| entry.size).sum()));
+        }
+    }
+    Ok(())
+}
+
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (number, multiplier) = match value.chars().last().map(|unit| unit.to_ascii_uppercase()) {
+        Some('K') => (&value[..value.len() - 1], 1u64 << 10),
+        Some('M') => (&value[..value.len() - 1], 1 << 20),
+        Some('G') => (&value[..value.len() - 1], 1 << 30),
+        Some('T') => (&value[..value.len() - 1], 1 << 40),
+        _ => (value, 1),
+    };
+    let number: f64 = number.trim().parse().map_err(|_| format!("invalid size \"{}\"", value))?;
+    Ok((number * multiplier as f64) as u64)
+}
@@ -0,0 +1,51 @@
+use crate::output::{format_size, OutputFormat};
+use crate::{output, GlobalOpts};
+use clap::Args;
+use std::error::Error;
+use wrum_lib::disk_usage;
+use wrum_lib::disk_usage::EditorUsage;
+
+#[derive(Debug, Args)]
+pub struct DiskUsageArgs {}
+
+pub fn execute(_args: DiskUsageArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let usages = disk_usage::get_editors_usage()?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("diskUsage", &usages)?,
+        OutputFormat::Tsv => usages.iter().for_each(print_usage_tsv),
+        OutputFormat::Text => {
+            usages.iter().for_each(print_usage);
+            println!("Total: {}", format_size(usages.iter().map(|usage| usage.total_size).sum()));
+        }
+    }
+    Ok(0)
+}
+
+fn print_usage(usage: &EditorUsage) {
+    println!("{} ({}) {} {}", usage.editor.version, String::from(usage.editor.arch.clone()), format_size(usage.total_size), usage.path.display());
+    for module in &usage.modules {
+        println!("  {} {}", module.id, format_size(module.size));
+    }
+    if !usage.downloads.is_empty() {
+        let downloads_size = usage.downloads.iter().map(|download| download.size).sum();
+        println!("  leftover downloads: {} in {} files", format_size(downloads_size), usage.downloads.len());
+    }
+    if !usage.stale_locks.is_empty() {
+        println!("  stale lock files: {}", usage.stale_locks.len());
+    }
+}
+
+fn print_usage_tsv(usage: &EditorUsage) {
+    let version = usage.editor.version.to_string();
+    let arch = String::from(usage.editor.arch.clone());
+    output::print_tsv([version.as_str(), arch.as_str(), "editor", &usage.path.display().to_string(), &usage.total_size.to_string()]);
+    for module in &usage.modules {
+        output::print_tsv([version.as_str(), arch.as_str(), "module", &module.id, &module.size.to_string()]);
+    }
+    for download in &usage.downloads {
+        output::print_tsv([version.as_str(), arch.as_str(), "download", &download.path.display().to_string(), &download.size.to_string()]);
+    }
+    for lock_path in &usage.stale_locks {
+        output::print_tsv([version.as_str(), arch.as_str(), "lock", &lock_path.display().to_string(), "0"]);
+    }
+}
@@ -1,9 +1,12 @@
+use crate::output::{InstalledEditor, MajorReleaseOutput, OutputFormat};
 use crate::system::call_hub_command;
-use crate::{system, GlobalOpts};
+use crate::{output, system, GlobalOpts};
 use clap::Args;
+use serde::Serialize;
 use std::error::Error;
 use std::path::PathBuf;
-use wrum_lib::live_api::latest_major_releases::ItemLatestUnityReleaseDownloads;
+use wrum_lib::editors::info::EditorInfo;
+use wrum_lib::live_api::latest_major_releases::{ItemLatestUnityReleaseDownloads, LatestMajorReleasesGetUnityReleaseMajorVersions};
 use wrum_lib::{editors, live_api};
 
 #[derive(Debug, Args)]
@@ -22,6 +25,14 @@ pub struct EditorsArgs {
     add: Option<PathBuf>,
 }
 
+#[derive(Serialize)]
+struct EditorsDocument<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    releases: Option<Vec<MajorReleaseOutput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installed: Option<Vec<InstalledEditor<'a>>>,
+}
+
 pub fn execute(args: EditorsArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     if let Some(path) = args.add {
         add_path(path, global_opt)
@@ -30,11 +41,20 @@ pub fn execute(args: EditorsArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn
     }
 }
 
-fn add_path(path: PathBuf, _global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
-    match call_hub_command(["editors", "--add", path.to_str().unwrap()]) {
-        Ok(status) => Ok(status.code().unwrap()),
-        Err(error) => Err(error.into()),
+fn add_path(path: PathBuf, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    if global_opt.hub {
+        return match call_hub_command(["editors", "--add", path.to_str().unwrap()]) {
+            Ok(status) => Ok(status.code().unwrap()),
+            Err(error) => Err(error.into()),
+        };
     }
+    let editor = editors::add_editor(path)?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("editor", &InstalledEditor::from(&editor))?,
+        OutputFormat::Tsv => print_installed_editors(vec![editor], OutputFormat::Tsv),
+        OutputFormat::Text => println!("Added {} at {}", editor.version, editor.path.display()),
+    }
+    Ok(0)
 }
 
 fn list_editors(releases: bool, installed: bool, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
@@ -49,20 +69,45 @@ fn list_editors(releases: bool, installed: bool, global_opt: GlobalOpts) -> Resu
             Err(error) => Err(error.into()),
         }
     } else {
-        match (releases, installed) {
-            (false, true) => print_installed_editors(),
-            (true, false) => print_major_releases(),
-            _ => {
-                print_major_releases()?;
-                print_installed_editors()
+        let (releases, installed) = match (releases, installed) {
+            (false, false) => (true, true),
+            selection => selection,
+        };
+        let major_releases = match releases {
+            true => Some(live_api::get_major_release_list()?),
+            false => None,
+        };
+        let installed_editors = match installed {
+            true => Some(editors::list_installed_editors()?),
+            false => None,
+        };
+        match global_opt.output {
+            OutputFormat::Json => output::print_json(
+                "editors",
+                &EditorsDocument {
+                    releases: major_releases.as_ref().map(|releases| releases.iter().map(MajorReleaseOutput::from).collect()),
+                    installed: installed_editors.as_ref().map(|editors| editors.iter().map(InstalledEditor::from).collect()),
+                },
+            )?,
+            format => {
+                if let Some(major_releases) = major_releases {
+                    print_major_releases(major_releases, format);
+                }
+                if let Some(installed_editors) = installed_editors {
+                    print_installed_editors(installed_editors, format);
+                }
             }
         }
+        Ok(0)
     }
 }
 
-fn print_installed_editors() -> Result<i32, Box<dyn Error>> {
-    let editors = editors::list_installed_editors()?;
+fn print_installed_editors(editors: Vec<EditorInfo>, format: OutputFormat) {
     for editor in editors {
+        if format == OutputFormat::Tsv {
+            output::print_tsv(["installed".to_string(), editor.version.to_string(), String::from(editor.arch), editor.executable_path.display().to_string()]);
+            continue;
+        }
         match system::get_installed_arch_string(editor.arch) {
             None => {
                 println!("{}, installed at {}", editor.version, editor.executable_path.to_str().unwrap());
@@ -72,16 +117,17 @@ fn print_installed_editors() -> Result<i32, Box<dyn Error>> {
             }
         }
     }
-    Ok(0)
 }
 
-fn print_major_releases() -> Result<i32, Box<dyn Error>> {
-    let editors = live_api::get_major_release_list()?;
+fn print_major_releases(editors: Vec<LatestMajorReleasesGetUnityReleaseMajorVersions>, format: OutputFormat) {
     for editor in editors {
         let latest_release = editor.item.latest_unity_release;
         let full_version = latest_release.version;
         for download in latest_release.downloads {
             match download {
+                ItemLatestUnityReleaseDownloads::UnityReleaseHubDownload(download) if format == OutputFormat::Tsv => {
+                    output::print_tsv(["release".to_string(), full_version.clone(), format!("{:?}", download.architecture).to_lowercase()]);
+                }
                 ItemLatestUnityReleaseDownloads::UnityReleaseHubDownload(download) => match system::get_release_arch_string(download.architecture) {
                     None => {
                         println!("{}", full_version);
@@ -93,5 +139,4 @@ fn print_major_releases() -> Result<i32, Box<dyn Error>> {
             }
         }
     }
-    Ok(0)
 }
@@ -0,0 +1,90 @@
+use crate::output::{format_size, OutputFormat};
+use crate::{output, GlobalOpts};
+use clap::Args;
+use serde::Serialize;
+use std::error::Error;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use wrum_lib::disk_usage;
+use wrum_lib::disk_usage::CleanupPlan;
+
+#[derive(Debug, Args)]
+pub struct GcArgs {
+    ///folder containing Unity projects, searched for the editor versions in use. Replaces the workspaceRoots of the config. You can specify multiple values, separated by spaces.
+    #[clap(long, short, num_args = 1..)]
+    workspace_root: Option<Vec<PathBuf>>,
+    ///only print what would be removed
+    #[clap(long, default_value_t = false)]
+    dry_run: bool,
+    ///remove without asking for confirmation
+    #[clap(long, short, default_value_t = false)]
+    yes: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GcDocument<'a> {
+    #[serde(flatten)]
+    plan: &'a CleanupPlan,
+    removed: bool,
+    freed_size: u64,
+}
+
+pub fn execute(args: GcArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let plan = disk_usage::plan_cleanup(args.workspace_root)?;
+    match global_opt.output {
+        OutputFormat::Text => print_plan(&plan),
+        OutputFormat::Tsv => print_plan_tsv(&plan),
+        OutputFormat::Json => {}
+    }
+
+    let removed = !plan.is_empty() && !args.dry_run && (args.yes || confirm()?);
+    let freed_size = match removed {
+        true => disk_usage::clean_up(&plan)?,
+        false => 0,
+    };
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("gc", &GcDocument { plan: &plan, removed, freed_size })?,
+        OutputFormat::Text if removed => println!("Freed {}.", format_size(freed_size)),
+        _ => {}
+    }
+    Ok(0)
+}
+
+fn confirm() -> Result<bool, Box<dyn Error>> {
+    eprint!("Remove these files? [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_plan(plan: &CleanupPlan) {
+    if plan.is_empty() {
+        println!("Nothing to clean up.");
+        return;
+    }
+    for editor in &plan.unused_editors {
+        println!("unused editor {} ({}) {} {}", editor.editor.version, String::from(editor.editor.arch.clone()), format_size(editor.total_size), editor.path.display());
+    }
+    for download in &plan.downloads {
+        println!("download {} {}", format_size(download.size), download.path.display());
+    }
+    for lock_path in &plan.stale_locks {
+        println!("lock file {}", lock_path.display());
+    }
+    println!("{} can be reclaimed.", format_size(plan.reclaimable_size()));
+}
+
+fn print_plan_tsv(plan: &CleanupPlan) {
+    for editor in &plan.unused_editors {
+        output::print_tsv(["editor", &editor.path.display().to_string(), &editor.total_size.to_string()]);
+    }
+    for download in &plan.downloads {
+        output::print_tsv(["download", &download.path.display().to_string(), &download.size.to_string()]);
+    }
+    for lock_path in &plan.stale_locks {
+        output::print_tsv(["lock", &lock_path.display().to_string(), "0"]);
+    }
+}
@@ -1,17 +1,25 @@
 use crate::system::call_hub_command;
-use crate::GlobalOpts;
+use crate::{output, GlobalOpts};
 use clap::Args;
 use std::error::Error;
+use std::path::PathBuf;
 use crate::install_modules::install_modules;
+use wrum_lib::config;
+use wrum_lib::editors::deep_link::HubDeepLink;
+use wrum_lib::version::selector::VersionSelector;
+use wrum_lib::version::UnityVersion;
 
 #[derive(Debug, Args)]
 pub struct InstallArgs {
-    ///editor version to be installed (e.g. 2019.1.11f1) - required
-    #[clap(long, short)]
-    version: String,
+    ///editor version to be installed (e.g. 2019.1.11f1, 2022.3, lts, latest, latest-beta or ">=2022.3.10f1, <2022.4") - required
+    #[clap(long, short, required_unless_present = "link")]
+    version: Option<VersionSelector>,
     ///changeset of the editor if it is not in the release list (e.g. 9b001d489a54) - required if the version is not in the releases
     #[clap(long, short)]
     changeset: Option<String>,
+    ///Unity Hub link of the editor to be installed (e.g. unityhub://2022.3.10f1/ff3792e53c62)
+    #[clap(long, conflicts_with_all = ["version", "changeset"])]
+    link: Option<HubDeepLink>,
     ///the module id. The followings are the available values depending on version. You can specify multiple values, separated by spaces.
     #[clap(long, short, num_args = 1..)]
     module: Option<Vec<String>>,
@@ -24,13 +32,27 @@ pub struct InstallArgs {
     ///editor architecture to install (x86_64 or arm64)
     #[clap(long, short)]
     architecture: Option<String>,
+    ///base url of the download archive used to install by changeset (e.g. https://download.unity3d.com/download_unity/)
+    #[clap(long)]
+    download_base_url: Option<String>,
+    ///installs the editor from a local archive file instead of downloading it (e.g. ./Unity-2022.3.10f1.tar.xz). Requires an exact version
+    #[clap(long, conflicts_with_all = ["changeset", "link"])]
+    from_archive: Option<PathBuf>,
 }
 
 pub fn execute(args: InstallArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     let include_child_modules = args.cm || args.child_modules;
-    if global_opt.hub {
-        let mut hub_arguments = Vec::from_iter([String::from("install"), String::from("--version"), args.version]);
-        if let Some(changeset) = args.changeset {
+    let (version, changeset) = match (args.link, args.version) {
+        (Some(link), _) => (link.version, Some(link.changeset)),
+        (None, Some(selector)) if args.from_archive.is_some() && !selector.is_exact() => {
+            return Err("Specify the exact version of the editor in the archive".into())
+        }
+        (None, Some(selector)) => (resolve_version(&selector)?, args.changeset),
+        (None, None) => return Err("Specify a version or a Unity Hub link".into()),
+    };
+    if global_opt.hub && args.from_archive.is_none() {
+        let mut hub_arguments = Vec::from_iter([String::from("install"), String::from("--version"), version.to_string()]);
+        if let Some(changeset) = changeset {
             hub_arguments.push(String::from("--changeset"));
             hub_arguments.push(changeset);
         }
@@ -53,16 +75,43 @@ pub fn execute(args: InstallArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn
             Err(error) => Err(error.into()),
         }
     } else {
-        install_editor(&args.version, args.module, args.architecture, include_child_modules)
+        if let Some(download_base_url) = args.download_base_url {
+            let mut config = config::get_config()?;
+            config.download_base_url = download_base_url;
+            config::set_config(config);
+        }
+        match args.from_archive {
+            Some(archive_path) => {
+                wrum_lib::editors::install_editor_from_archive(&version, args.architecture.clone(), &archive_path)?;
+                install_editor_modules(&version, args.module, args.architecture.clone(), include_child_modules)?;
+            }
+            None => {
+                install_editor(&version, changeset.as_deref(), args.module, args.architecture.clone(), include_child_modules)?;
+            }
+        }
+        output::print_install_result(global_opt.output, &version, args.architecture)?;
+        Ok(0)
     }
 }
 
-pub fn install_editor(version: &str, modules: Option<Vec<String>>, arch: Option<String>, include_children: bool) -> Result<i32, Box<dyn Error>> {
-    wrum_lib::editors::install_editor(version, arch.clone())?;
+pub fn install_editor(version: &UnityVersion, changeset: Option<&str>, modules: Option<Vec<String>>, arch: Option<String>, include_children: bool) -> Result<i32, Box<dyn Error>> {
+    wrum_lib::editors::install_editor(version, arch.clone(), changeset)?;
+    install_editor_modules(version, modules, arch, include_children)
+}
+
+fn install_editor_modules(version: &UnityVersion, modules: Option<Vec<String>>, arch: Option<String>, include_children: bool) -> Result<i32, Box<dyn Error>> {
     if let Some(modules) = modules {
         install_modules(version, modules, arch, include_children)?;
     } else {
-        wrum_lib::workarounds::apply_bee_workaround(&version, arch)?;
+        wrum_lib::workarounds::apply_bee_workaround(version, arch)?;
     }
     Ok(0)
 }
+
+pub fn resolve_version(selector: &VersionSelector) -> Result<UnityVersion, Box<dyn Error>> {
+    let version = selector.resolve()?;
+    if !selector.is_exact() {
+        eprintln!("Resolved \"{}\" to {}", selector, version);
+    }
+    Ok(version)
+}
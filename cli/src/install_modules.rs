@@ -1,13 +1,16 @@
 use crate::system::call_hub_command;
-use crate::GlobalOpts;
+use crate::{output, GlobalOpts};
 use clap::Args;
 use std::error::Error;
+use std::path::PathBuf;
+use wrum_lib::version::selector::VersionSelector;
+use wrum_lib::version::UnityVersion;
 
 #[derive(Debug, Args)]
 pub struct InstallModulesArgs {
-    ///version of the editor to add the module to - required
+    ///version of the editor to add the module to (e.g. 2019.1.11f1, 2022.3 or ">=2022.3, <2022.4"), the newest matching installed editor is used - required
     #[clap(long, short)]
-    version: String,
+    version: VersionSelector,
     ///the module id. The followings are the available values depending on version. You can specify multiple values, separated by spaces.
     #[clap(long, short, num_args = 1..)]
     module: Vec<String>,
@@ -20,13 +23,26 @@ pub struct InstallModulesArgs {
     child_modules: bool,
     ///editor architecture to install (x86_64 or arm64)
     #[clap(long, short)]
-    architecture: Option<String>
+    architecture: Option<String>,
+    ///installs the module from a local archive file instead of downloading it (e.g. ./UnitySetup-Android-Support-for-Editor-2022.3.10f1.tar.xz). Requires a single module
+    #[clap(long)]
+    from_archive: Option<PathBuf>,
 }
 
 pub fn execute(args: InstallModulesArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     let include_children = args.cm || args.child_modules;
+    let version = wrum_lib::editors::resolve_installed_version(&args.version)?;
+    if let Some(archive_path) = args.from_archive {
+        let [module] = args.module.as_slice() else {
+            return Err("Specify exactly one module to install from the archive".into());
+        };
+        wrum_lib::modules::install_module_from_archive(&version, module, &archive_path, args.architecture.clone())?;
+        wrum_lib::workarounds::apply_bee_workaround(&version, args.architecture.clone())?;
+        output::print_install_result(global_opt.output, &version, args.architecture)?;
+        return Ok(0);
+    }
     if global_opt.hub {
-        let mut hub_arguments = Vec::from_iter([String::from("install-modules"), String::from("--version"), args.version]);
+        let mut hub_arguments = Vec::from_iter([String::from("install-modules"), String::from("--version"), version.to_string()]);
         for module in args.module {
             hub_arguments.push(String::from("--module"));
             hub_arguments.push(module);
@@ -39,16 +55,18 @@ pub fn execute(args: InstallModulesArgs, global_opt: GlobalOpts) -> Result<i32,
             Err(error) => Err(error.into()),
         }
     } else {
-        install_modules(&args.version, args.module, args.architecture, include_children)
+        install_modules(&version, args.module, args.architecture.clone(), include_children)?;
+        output::print_install_result(global_opt.output, &version, args.architecture)?;
+        Ok(0)
     }
 }
 
-pub fn install_modules(version: &str, modules: Vec<String>, arch: Option<String>, include_children: bool) -> Result<i32, Box<dyn Error>> {
+pub fn install_modules(version: &UnityVersion, modules: Vec<String>, arch: Option<String>, include_children: bool) -> Result<i32, Box<dyn Error>> {
     let mut modules_in_args: Vec<String> = vec![];
     for module in modules {
         modules_in_args.append(&mut module.split(' ').map(str::to_string).collect());
     }
     wrum_lib::modules::install_modules(version, modules_in_args, arch.clone(), include_children)?;
-    wrum_lib::workarounds::apply_bee_workaround(&version, arch)?;
+    wrum_lib::workarounds::apply_bee_workaround(version, arch)?;
     Ok(0)
 }
@@ -1,8 +1,10 @@
 use crate::system::call_hub_command;
-use crate::GlobalOpts;
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
+use serde::Serialize;
 use clap::Args;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use wrum_lib::install::{get_install_path, set_secondary_install_path};
 
 #[derive(Debug, Args)]
@@ -18,6 +20,11 @@ pub struct InstallPathArgs {
     set: Option<PathBuf>,
 }
 
+#[derive(Serialize)]
+struct InstallPathDocument<'a> {
+    path: &'a Path,
+}
+
 pub fn execute(args: InstallPathArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     if let Some(path) = args.set {
         set_path(path, global_opt)
@@ -37,7 +44,7 @@ fn set_path(path: PathBuf, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>
     } else {
         match set_secondary_install_path(path, true) {
             Ok(_) => Ok(0),
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 }
@@ -45,7 +52,7 @@ fn set_path(path: PathBuf, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>
 fn reset_path() -> Result<i32, Box<dyn Error>> {
     match set_secondary_install_path(PathBuf::from(""), false) {
         Ok(_) => Ok(0),
-        Err(error) => Err(error.into()),
+        Err(error) => Err(error),
     }
 }
 
@@ -58,10 +65,14 @@ fn print_path(global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     } else {
         match get_install_path() {
             Ok(path) => {
-                println!("{}", path.to_str().unwrap());
+                match global_opt.output {
+                    OutputFormat::Json => output::print_json("installPath", &InstallPathDocument { path: &path })?,
+                    OutputFormat::Tsv => output::print_tsv([path.to_str().unwrap()]),
+                    OutputFormat::Text => println!("{}", path.to_str().unwrap()),
+                }
                 Ok(0)
             }
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 }
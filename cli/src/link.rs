@@ -0,0 +1,34 @@
+use crate::install::install_editor;
+use crate::output::OutputFormat;
+use crate::{output, system, GlobalOpts};
+use clap::Args;
+use std::error::Error;
+use wrum_lib::editors::deep_link::HubDeepLink;
+
+#[derive(Debug, Args)]
+pub struct LinkArgs {
+    ///Unity Hub link of the editor to be installed (e.g. unityhub://2022.3.10f1/ff3792e53c62)
+    #[clap(required_unless_present = "register")]
+    link: Option<HubDeepLink>,
+    ///register wrum as the handler of unityhub:// links
+    #[clap(long, default_value_t = false, conflicts_with = "link")]
+    register: bool,
+}
+
+pub fn execute(args: LinkArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    match args.link {
+        Some(link) => {
+            eprintln!("Installing {} ({})", link.version, link.changeset);
+            install_editor(&link.version, Some(&link.changeset), None, None, false)?;
+            output::print_install_result(global_opt.output, &link.version, None)?;
+            Ok(0)
+        }
+        None => {
+            system::register_link_handler(std::env::current_exe()?)?;
+            if global_opt.output == OutputFormat::Text {
+                println!("wrum is now registered as the unityhub:// link handler");
+            }
+            Ok(0)
+        }
+    }
+}
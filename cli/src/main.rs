@@ -1,11 +1,26 @@
+mod cache;
+mod disk_usage;
 mod editors;
+mod gc;
 mod install;
 mod install_modules;
 mod install_path;
+mod link;
+mod output;
+mod progress;
 mod system;
+mod templates;
+mod uninstall;
+mod uninstall_modules;
+mod verify;
 mod project;
+mod release_notes;
+mod releases;
 
+use crate::output::OutputFormat;
 use clap::{Args, Parser, Subcommand};
+use std::error::Error;
+use wrum_lib::config;
 
 /// Written in Rust Unity Manager
 #[derive(Debug, Parser)]
@@ -23,15 +38,37 @@ enum Command {
     ///list the releases and installed editors (alias: e)
     #[clap(alias("e"))]
     Editors(editors::EditorsArgs),
+    ///browse every release published by Unity, filtered by stream, version, platform and architecture (alias: r)
+    #[clap(alias("r"))]
+    Releases(releases::ReleasesArgs),
+    ///print the release notes of a release or of every release between two versions (alias: rn)
+    #[clap(alias("rn"))]
+    ReleaseNotes(release_notes::ReleaseNotesArgs),
     ///set/get the path where the Unity editors will be installed (alias: ip)
     #[clap(alias("ip"))]
     InstallPath(install_path::InstallPathArgs),
     ///installs a new editor either from the releases list or archive (alias: i)
     #[clap(alias("i"))]
     Install(install::InstallArgs),
+    ///remove an installed editor with all of its modules (alias: u)
+    #[clap(alias("u"))]
+    Uninstall(uninstall::UninstallArgs),
     ///download and install a module (e.g. build support) to an installed editor (alias: im)
     #[clap(alias("im"))]
     InstallModules(install_modules::InstallModulesArgs),
+    ///remove a module and the files it installed from an installed editor (alias: um)
+    #[clap(alias("um"))]
+    UninstallModules(uninstall_modules::UninstallModulesArgs),
+    ///report the disk space used by each installed editor, its modules, leftover downloads and stale lock files
+    Du(disk_usage::DiskUsageArgs),
+    ///remove leftover downloads, stale lock files and editors no project in the workspace roots uses
+    Gc(gc::GcArgs),
+    ///list, prune or clear the shared download cache
+    Cache(cache::CacheArgs),
+    ///check the files of an installed editor and its modules, and optionally repair them
+    Verify(verify::VerifyArgs),
+    ///install the editor from a unityhub:// link, or register wrum as the handler of these links
+    Link(link::LinkArgs),
     ///print project's editor version
     #[clap(alias("pv"))]
     ProjectEditorVersion(project::ProjectEditorVersionArgs),
@@ -43,6 +80,9 @@ enum Command {
     ///create a new project using a specific editor version
     #[clap(alias("cp"))]
     CreateProject(project::ProjectCreateArgs),
+    ///list the project templates compatible with an editor version (alias: t)
+    #[clap(alias("t"))]
+    Templates(templates::TemplatesArgs),
 }
 
 #[derive(Debug, Args)]
@@ -56,21 +96,25 @@ struct GlobalOpts {
     ///pass errors flag to Unity Hub CLI
     #[clap(long, default_value_t = false)]
     errors: bool,
+    ///serve release information only from the local cache, without querying the API
+    #[clap(long, default_value_t = false, global = true)]
+    offline: bool,
+    ///ignore the cached release information and query the API again
+    #[clap(long, default_value_t = false, global = true, conflicts_with = "offline")]
+    refresh: bool,
+    ///install downloads even when they don't match the integrity hash published by Unity, for mirrors that re-pack archives
+    #[clap(long, default_value_t = false, global = true)]
+    skip_integrity: bool,
+    ///format of the command output: human readable text, versioned JSON documents or tab separated values
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    output: OutputFormat,
 }
 
 fn main() {
     let args = App::parse();
     let global_opt = args.global_opts;
-    let exit_code = match args.command {
-        Command::InstallPath(args) => install_path::execute(args, global_opt),
-        Command::Editors(args) => editors::execute(args, global_opt),
-        Command::Install(args) => install::execute(args, global_opt),
-        Command::InstallModules(args) => install_modules::execute(args, global_opt),
-        Command::ProjectEditorVersion(args) => project::editor_version(args, global_opt),
-        Command::OpenProject(args) => project::open(args, global_opt),
-        Command::ExecuteProject(args) => project::execute(args, global_opt),
-        Command::CreateProject(args) => project::create(args, global_opt),
-    };
+    progress::set_reporter(global_opt.output == OutputFormat::Text);
+    let exit_code = apply_global_config(&global_opt).and_then(|_| run(args.command, global_opt));
     match exit_code {
         Ok(code) => {
             std::process::exit(code);
@@ -81,3 +125,35 @@ fn main() {
         }
     }
 }
+
+fn run(command: Command, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    match command {
+        Command::InstallPath(args) => install_path::execute(args, global_opt),
+        Command::Editors(args) => editors::execute(args, global_opt),
+        Command::Releases(args) => releases::execute(args, global_opt),
+        Command::ReleaseNotes(args) => release_notes::execute(args, global_opt),
+        Command::Install(args) => install::execute(args, global_opt),
+        Command::Uninstall(args) => uninstall::execute(args, global_opt),
+        Command::InstallModules(args) => install_modules::execute(args, global_opt),
+        Command::UninstallModules(args) => uninstall_modules::execute(args, global_opt),
+        Command::Du(args) => disk_usage::execute(args, global_opt),
+        Command::Gc(args) => gc::execute(args, global_opt),
+        Command::Cache(args) => cache::execute(args, global_opt),
+        Command::Verify(args) => verify::execute(args, global_opt),
+        Command::Link(args) => link::execute(args, global_opt),
+        Command::ProjectEditorVersion(args) => project::editor_version(args, global_opt),
+        Command::OpenProject(args) => project::open(args, global_opt),
+        Command::ExecuteProject(args) => project::execute(args, global_opt),
+        Command::CreateProject(args) => project::create(args, global_opt),
+        Command::Templates(args) => templates::execute(args, global_opt),
+    }
+}
+
+fn apply_global_config(global_opt: &GlobalOpts) -> Result<(), Box<dyn Error>> {
+    let mut config = config::get_config()?;
+    config.offline |= global_opt.offline;
+    config.refresh = global_opt.refresh;
+    config.skip_integrity = global_opt.skip_integrity;
+    config::set_config(config);
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use wrum_lib::editors::info::{read_editor_info, EditorInfo};
+use wrum_lib::live_api::latest_major_releases::{ItemLatestUnityReleaseDownloads, LatestMajorReleasesGetUnityReleaseMajorVersions};
+use wrum_lib::live_api::release_list::{ReleaseListItem, ReleaseListItemDownloads};
+use wrum_lib::live_api::templates::{TemplateItem, TemplateItemVersions};
+use wrum_lib::modules;
+use wrum_lib::modules::info::ModuleInfo;
+use wrum_lib::version::UnityVersion;
+
+static SCHEMA_VERSION: u32 = 1;
+static SIZE_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Tsv,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Document<'a, T: Serialize> {
+    schema_version: u32,
+    kind: &'a str,
+    data: &'a T,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledEditor<'a> {
+    #[serde(flatten)]
+    pub info: &'a EditorInfo,
+    pub path: &'a Path,
+    pub executable_path: &'a Path,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallResult<'a> {
+    #[serde(flatten)]
+    pub editor: InstalledEditor<'a>,
+    pub modules: Vec<ModuleInfo>,
+}
+
+impl<'a> From<&'a EditorInfo> for InstalledEditor<'a> {
+    fn from(info: &'a EditorInfo) -> Self {
+        InstalledEditor {
+            info,
+            path: &info.path,
+            executable_path: &info.executable_path,
+        }
+    }
+}
+
+// The JSON documents are built from these structs rather than the API response types, so the
+// output stays stable when the GraphQL queries change.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseOutput {
+    pub version: String,
+    pub release_date: String,
+    pub stream: String,
+    pub recommended: bool,
+    pub short_revision: String,
+    pub entitlements: Vec<String>,
+    pub release_notes: FileOutput,
+    pub downloads: Vec<DownloadOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOutput {
+    pub url: String,
+    pub integrity: Option<String>,
+    #[serde(rename = "type")]
+    pub file_type: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadOutput {
+    pub platform: String,
+    pub architecture: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MajorReleaseOutput {
+    pub version: String,
+    pub latest_version: String,
+    pub architectures: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateOutput {
+    pub package_name: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub template_type: String,
+    pub category: String,
+    pub render_pipeline: String,
+    pub versions: Vec<TemplateVersionOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVersionOutput {
+    pub name: String,
+    pub is_latest: bool,
+    pub supported_unity_editor_versions: Vec<String>,
+    pub tarball: FileOutput,
+    pub dependencies: Vec<TemplateDependencyOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDependencyOutput {
+    pub name: String,
+    pub package_name: String,
+    pub version: String,
+}
+
+impl From<&ReleaseListItem> for ReleaseOutput {
+    fn from(release: &ReleaseListItem) -> Self {
+        ReleaseOutput {
+            version: release.version.clone(),
+            release_date: release.release_date.clone(),
+            stream: get_enum_name(&release.stream),
+            recommended: release.recommended,
+            short_revision: release.short_revision.clone(),
+            entitlements: release.entitlements.iter().map(get_enum_name).collect(),
+            release_notes: FileOutput {
+                url: release.release_notes.url.clone(),
+                integrity: release.release_notes.integrity.clone(),
+                file_type: get_enum_name(&release.release_notes.type_),
+            },
+            downloads: release
+                .downloads
+                .iter()
+                .map(|download| match download {
+                    ReleaseListItemDownloads::UnityReleaseHubDownload(download) => DownloadOutput {
+                        platform: get_enum_name(&download.platform),
+                        architecture: get_enum_name(&download.architecture),
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&LatestMajorReleasesGetUnityReleaseMajorVersions> for MajorReleaseOutput {
+    fn from(major_release: &LatestMajorReleasesGetUnityReleaseMajorVersions) -> Self {
+        let latest_release = &major_release.item.latest_unity_release;
+        MajorReleaseOutput {
+            version: major_release.item.version.clone(),
+            latest_version: latest_release.version.clone(),
+            architectures: latest_release
+                .downloads
+                .iter()
+                .map(|download| match download {
+                    ItemLatestUnityReleaseDownloads::UnityReleaseHubDownload(download) => get_enum_name(&download.architecture),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&TemplateItem> for TemplateOutput {
+    fn from(template: &TemplateItem) -> Self {
+        TemplateOutput {
+            package_name: template.package_name.clone(),
+            name: template.name.clone(),
+            description: template.description.clone(),
+            template_type: get_enum_name(&template.type_),
+            category: get_enum_name(&template.category),
+            render_pipeline: get_enum_name(&template.render_pipeline),
+            versions: template.versions.iter().map(TemplateVersionOutput::from).collect(),
+        }
+    }
+}
+
+impl From<&TemplateItemVersions> for TemplateVersionOutput {
+    fn from(version: &TemplateItemVersions) -> Self {
+        TemplateVersionOutput {
+            name: version.name.clone(),
+            is_latest: version.is_latest,
+            supported_unity_editor_versions: version.supported_unity_editor_versions.clone(),
+            tarball: FileOutput {
+                url: version.tarball.template_file.url.clone(),
+                integrity: Some(version.tarball.template_file.integrity.clone()),
+                file_type: get_enum_name(&version.tarball.template_file.type_),
+            },
+            dependencies: version
+                .dependencies
+                .iter()
+                .map(|dependency| TemplateDependencyOutput {
+                    name: dependency.name.clone(),
+                    package_name: dependency.package_name.clone(),
+                    version: dependency.version.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The GraphQL name of an API enum value, e.g. `LTS` or `X86_64`.
+fn get_enum_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+pub fn print_json<T: Serialize>(kind: &str, data: &T) -> Result<(), Box<dyn Error>> {
+    let document = Document {
+        schema_version: SCHEMA_VERSION,
+        kind,
+        data,
+    };
+    println!("{}", serde_json::to_string_pretty(&document)?);
+    Ok(())
+}
+
+pub fn print_tsv<I, S>(fields: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|field| field.as_ref().replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r"))
+        .collect();
+    println!("{}", fields.join("\t"));
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < SIZE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} {}", bytes, SIZE_UNITS[0]),
+        _ => format!("{:.1} {}", size, SIZE_UNITS[unit]),
+    }
+}
+
+pub fn print_install_result(format: OutputFormat, version: &UnityVersion, arch: Option<String>) -> Result<(), Box<dyn Error>> {
+    if format == OutputFormat::Text {
+        return Ok(());
+    }
+    let editor_path = wrum_lib::editors::get_installed_editor_path(version, arch)?.ok_or("Couldn't find the installed editor")?;
+    let editor_info = read_editor_info(PathBuf::from(&editor_path))?.ok_or("Couldn't read the installed editor")?;
+    let mut installed_modules: Vec<ModuleInfo> = get_modules(&editor_path)?.into_values().filter(|module| module.selected).collect();
+    installed_modules.sort_by(|a, b| a.id.cmp(&b.id));
+    match format {
+        OutputFormat::Json => print_json(
+            "install",
+            &InstallResult {
+                editor: InstalledEditor::from(&editor_info),
+                modules: installed_modules,
+            },
+        ),
+        _ => {
+            let module_ids: Vec<String> = installed_modules.into_iter().map(|module| module.id).collect();
+            print_tsv([editor_info.version.to_string(), String::from(editor_info.arch.clone()), editor_path.display().to_string(), module_ids.join(",")]);
+            Ok(())
+        }
+    }
+}
+
+fn get_modules(editor_path: &Path) -> Result<HashMap<String, ModuleInfo>, Box<dyn Error>> {
+    match editor_path.join("modules.json").exists() {
+        true => modules::read_modules_info(editor_path),
+        false => Ok(HashMap::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn release_output_uses_plain_values() {
+        let release: ReleaseListItem = serde_json::from_value(json!({
+            "version": "2022.3.10f1",
+            "releaseDate": "2023-09-26T00:00:00.000Z",
+            "stream": "LTS",
+            "recommended": true,
+            "shortRevision": "ff3792e53c62",
+            "entitlements": [],
+            "releaseNotes": { "url": "https://example.com/notes.md", "integrity": null, "type": "MD" },
+            "downloads": [{ "__typename": "UnityReleaseHubDownload", "platform": "LINUX", "architecture": "X86_64" }]
+        }))
+        .unwrap();
+
+        let output = serde_json::to_value(ReleaseOutput::from(&release)).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "version": "2022.3.10f1",
+                "releaseDate": "2023-09-26T00:00:00.000Z",
+                "stream": "LTS",
+                "recommended": true,
+                "shortRevision": "ff3792e53c62",
+                "entitlements": [],
+                "releaseNotes": { "url": "https://example.com/notes.md", "integrity": null, "type": "MD" },
+                "downloads": [{ "platform": "LINUX", "architecture": "X86_64" }]
+            })
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wrum_lib::progress;
+use wrum_lib::progress::{ProgressEvent, ProgressReporter};
+
+const LINE_PROGRESS_STEPS: u64 = 10;
+
+/// Animated bars for interactive terminals.
+pub struct TerminalReporter {
+    bars: MultiProgress,
+    active_bars: Mutex<HashMap<String, ProgressBar>>,
+}
+
+/// Plain lines for CI logs and redirected output.
+pub struct LineReporter {
+    reported_steps: Mutex<HashMap<String, u64>>,
+}
+
+/// Uses the animated reporter when stderr is a terminal and the output is meant to be read by a human.
+pub fn set_reporter(interactive: bool) {
+    let reporter: Arc<dyn ProgressReporter> = match interactive && std::io::stderr().is_terminal() {
+        true => Arc::new(TerminalReporter::new()),
+        false => Arc::new(LineReporter::new()),
+    };
+    progress::set_reporter(reporter);
+}
+
+impl TerminalReporter {
+    pub fn new() -> Self {
+        TerminalReporter { bars: MultiProgress::new(), active_bars: Mutex::new(HashMap::new()) }
+    }
+
+    fn add_bar(&self, id: &str, bar: ProgressBar) {
+        let bar = self.bars.add(bar);
+        bar.set_message(id.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        if let Some(previous) = self.active_bars.lock().unwrap().insert(id.to_string(), bar) {
+            previous.finish_and_clear();
+        }
+    }
+
+    fn finish_bar(&self, id: &str) {
+        if let Some(bar) = self.active_bars.lock().unwrap().remove(id) {
+            bar.finish_and_clear();
+            self.bars.remove(&bar);
+        }
+    }
+
+    fn println(&self, message: String) {
+        if self.bars.println(&message).is_err() {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+impl ProgressReporter for TerminalReporter {
+    fn report(&self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::DownloadStarted { id, size } => {
+                let bar = match size {
+                    Some(size) => ProgressBar::new(*size).with_style(download_style()),
+                    None => ProgressBar::new_spinner().with_style(spinner_style()).with_prefix("Downloading"),
+                };
+                self.add_bar(id, bar);
+            }
+            ProgressEvent::DownloadProgress { id, downloaded, .. } => {
+                if let Some(bar) = self.active_bars.lock().unwrap().get(id) {
+                    bar.set_position(*downloaded);
+                }
+            }
+            ProgressEvent::DownloadFinished { id } => self.finish_bar(id),
+            ProgressEvent::UnpackStarted { id } | ProgressEvent::VerifyStarted { id } => {
+                self.finish_bar(id);
+                let bar = ProgressBar::new_spinner().with_style(spinner_style());
+                bar.set_prefix(match event {
+                    ProgressEvent::VerifyStarted { .. } => "Verifying",
+                    _ => "Unpacking",
+                });
+                self.add_bar(id, bar);
+            }
+            ProgressEvent::UnpackFinished { id } | ProgressEvent::IntegrityMismatch { id } => {
+                self.finish_bar(id);
+                self.println(describe(event).unwrap_or_default());
+            }
+            _ => {
+                if let Some(message) = describe(event) {
+                    self.println(message);
+                }
+            }
+        }
+    }
+}
+
+impl LineReporter {
+    pub fn new() -> Self {
+        LineReporter { reported_steps: Mutex::new(HashMap::new()) }
+    }
+
+    // A line per tenth of the download keeps CI logs short.
+    fn get_progress_line(&self, id: &str, downloaded: u64, size: u64) -> Option<String> {
+        let step = downloaded * LINE_PROGRESS_STEPS / size;
+        let mut reported_steps = self.reported_steps.lock().unwrap();
+        let reported_step = reported_steps.entry(id.to_string()).or_insert(0);
+        if step <= *reported_step {
+            return None;
+        }
+        *reported_step = step;
+        Some(format!("Downloading {}: {:.2}%.", id, downloaded as f64 / size as f64 * 100.0))
+    }
+}
+
+impl ProgressReporter for LineReporter {
+    fn report(&self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::DownloadProgress { id, downloaded, size } if *size > 0 => {
+                if let Some(line) = self.get_progress_line(id, *downloaded, *size) {
+                    eprintln!("{}", line);
+                }
+            }
+            ProgressEvent::DownloadFinished { id } => {
+                self.reported_steps.lock().unwrap().remove(id);
+            }
+            _ => {
+                if let Some(message) = describe(event) {
+                    eprintln!("{}", message);
+                }
+            }
+        }
+    }
+}
+
+fn describe(event: &ProgressEvent) -> Option<String> {
+    let message = match event {
+        ProgressEvent::WaitingForLock { id } => format!("Another process is using \"{}\". Waiting...", id),
+        ProgressEvent::AlreadyInstalled { id } => format!("{} already installed!", id),
+        ProgressEvent::DownloadStarted { id, .. } => format!("Downloading {}.", id),
+        ProgressEvent::DownloadCached { id } => format!("Using the cached download of {}.", id),
+        ProgressEvent::VerifyStarted { id } => format!("Verifying {}.", id),
+        ProgressEvent::IntegrityMismatch { id } => format!("The download of {} is corrupted. Downloading it again...", id),
+        ProgressEvent::UnpackStarted { id } => format!("Unpacking {}.", id),
+        ProgressEvent::UnpackFinished { id } => format!("{} successfully installed.", id),
+        ProgressEvent::UninstallStarted { id } => format!("Uninstalling {}.", id),
+        ProgressEvent::UninstallFinished { id } => format!("{} successfully uninstalled.", id),
+        ProgressEvent::RepairStarted { id } => format!("Repairing {}.", id),
+        ProgressEvent::WorkaroundApplied { path } => format!("Applying bee workaround to '{}'", path.display()),
+        ProgressEvent::RequestRetrying { error, delay } => format!("{}. Retrying in {:.1}s...", error, delay.as_secs_f32()),
+        ProgressEvent::Warning { message } => format!("Warning: {}", message),
+        _ => return None,
+    };
+    Some(message)
+}
+
+fn download_style() -> ProgressStyle {
+    ProgressStyle::with_template("{msg} [{bar:30}] {bytes}/{total_bytes} {bytes_per_sec} {eta}")
+        .unwrap()
+        .progress_chars("=> ")
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner} {prefix} {msg}").unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_reporter_prints_a_line_per_tenth_of_a_download() {
+        let reporter = LineReporter::new();
+
+        let printed: Vec<u64> = (0..=1000).step_by(25).filter(|downloaded| reporter.get_progress_line("editor", *downloaded, 1000).is_some()).collect();
+        assert_eq!(printed, [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000]);
+        assert_eq!(reporter.get_progress_line("module", 550, 1000).as_deref(), Some("Downloading module: 55.00%."));
+        assert!(reporter.get_progress_line("module", 599, 1000).is_none());
+        assert!(reporter.get_progress_line("module", 300, 1000).is_none());
+    }
+
+    #[test]
+    fn line_reporter_starts_over_after_a_finished_download() {
+        let reporter = LineReporter::new();
+        assert!(reporter.get_progress_line("editor", 900, 1000).is_some());
+
+        reporter.report(&ProgressEvent::DownloadFinished { id: String::from("editor") });
+        assert!(reporter.get_progress_line("editor", 150, 1000).is_some());
+    }
+}
@@ -1,10 +1,14 @@
-use crate::GlobalOpts;
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
 use clap::Args;
+use serde::Serialize;
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use crate::install::install_editor;
+use crate::install::{install_editor, resolve_version};
+use wrum_lib::version::selector::VersionSelector;
+use wrum_lib::version::UnityVersion;
 
 #[derive(Debug, Args)]
 pub struct ProjectEditorVersionArgs {
@@ -62,20 +66,43 @@ pub struct ProjectExecuteArgs {
 
 #[derive(Debug, Args)]
 pub struct ProjectCreateArgs {
-    ///editor version to use for project creation
+    ///editor version to use for project creation (e.g. 2019.1.11f1, 2022.3, lts or latest)
     #[clap(long, short)]
-    version: String,
+    version: VersionSelector,
     ///path where the project should be created
     #[clap(long)]
     path: PathBuf,
     ///editor architecture (x86_64 or arm64)
     #[clap(long)]
     architecture: Option<String>,
+    ///template to create the project from (e.g. com.unity.template.3d or com.unity.template.3d@8.1.0)
+    #[clap(long)]
+    template: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectDocument<'a> {
+    path: &'a Path,
+    editor_version: &'a UnityVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<&'a str>,
 }
 
-pub fn editor_version(args: ProjectEditorVersionArgs, _global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
-    let editor_version = wrum_lib::projects::get_project_editor_version(args.path)?;
-    println!("{}", editor_version);
+pub fn editor_version(args: ProjectEditorVersionArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let editor_version = wrum_lib::projects::get_project_editor_version(&args.path)?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json(
+            "project",
+            &ProjectDocument {
+                path: &args.path,
+                editor_version: &editor_version,
+                template: None,
+            },
+        )?,
+        OutputFormat::Tsv => output::print_tsv([editor_version.to_string(), args.path.display().to_string()]),
+        OutputFormat::Text => println!("{}", editor_version),
+    }
     Ok(0)
 }
 
@@ -91,10 +118,14 @@ pub fn open(args: ProjectOpenArgs, _global_opt: GlobalOpts) -> Result<i32, Box<d
     Ok(0)
 }
 
-pub fn execute(args: ProjectExecuteArgs, _global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+pub fn execute(args: ProjectExecuteArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
     let editor_path = get_or_install_editor(&args.path, args.module, args.architecture)?;
     let executable_path = wrum_lib::system_info::get_editor_executable_path(editor_path);
-    let license_lock_file = wrum_lib::license::lock_license(&args.username)?;
+    let (license_lock_file, license_lock_path) = wrum_lib::license::lock_license(&args.username)?;
+    // JSON and TSV documents must stay parseable, so the lock path is only printed for humans.
+    if global_opt.output == OutputFormat::Text {
+        println!("{}", license_lock_path.display());
+    }
     Command::new(executable_path.clone())
         .arg("-projectPath")
         .arg(&args.path)
@@ -117,7 +148,7 @@ pub fn execute(args: ProjectExecuteArgs, _global_opt: GlobalOpts) -> Result<i32,
         .status()?;
     let can_return_license = wrum_lib::license::release_license(&args.username, license_lock_file)?;
     if can_return_license {
-        println!("Return license");
+        eprintln!("Return license");
         Command::new(executable_path.clone())
             .arg("-projectPath")
             .arg(&args.path)
@@ -133,14 +164,15 @@ pub fn execute(args: ProjectExecuteArgs, _global_opt: GlobalOpts) -> Result<i32,
             .arg("-returnlicense")
             .status()?;
     } else {
-        println!("License is still in use");
+        eprintln!("License is still in use");
     }
     Ok(0)
 }
 
-pub fn create(args: ProjectCreateArgs, _global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
-    install_editor(&args.version, None, args.architecture.clone(), false)?;
-    let editor_path = wrum_lib::editors::get_installed_editor_path(&args.version, args.architecture.clone())?;
+pub fn create(args: ProjectCreateArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let version = resolve_version(&args.version)?;
+    install_editor(&version, None, None, args.architecture.clone(), false)?;
+    let editor_path = wrum_lib::editors::get_installed_editor_path(&version, args.architecture.clone())?;
     let editor_path = match editor_path {
         Some(path) => path,
         None => return Err("Something went wrong. Failed to install and obtain an editor".into())
@@ -150,17 +182,54 @@ pub fn create(args: ProjectCreateArgs, _global_opt: GlobalOpts) -> Result<i32, B
             fs::create_dir_all(parent)?;
         }
     }
-    let executable_path = wrum_lib::system_info::get_editor_executable_path(editor_path);
-    Command::new(executable_path)
-        .arg("-createProject")
-        .arg(&args.path)
-        .status()?;
+    let executable_path = wrum_lib::system_info::get_editor_executable_path(&editor_path);
+    match &args.template {
+        None => {
+            Command::new(executable_path)
+                .arg("-createProject")
+                .arg(&args.path)
+                .status()?;
+        }
+        Some(template) => {
+            if args.path.exists() && fs::read_dir(&args.path)?.next().is_some() {
+                return Err("Project path already exists and isn't empty".into());
+            }
+            let (package_name, template_version) = match template.split_once('@') {
+                Some((package_name, template_version)) => (package_name, Some(template_version)),
+                None => (template.as_str(), None),
+            };
+            let (_, template_version) = wrum_lib::templates::find_template(package_name, template_version, &version)?;
+            eprintln!("Applying template {}@{}", package_name, template_version.name);
+            wrum_lib::templates::apply_template(&template_version, &args.path)?;
+            Command::new(executable_path)
+                .arg("-projectPath")
+                .arg(&args.path)
+                .status()?;
+        }
+    }
+    if let Some(editor_info) = wrum_lib::editors::info::read_editor_info(editor_path)? {
+        if let Err(err) = wrum_lib::hub::add_hub_project(&args.path, &editor_info) {
+            eprintln!("Warning: failed to update the Unity Hub project list: {}", err);
+        }
+    }
+    match global_opt.output {
+        OutputFormat::Json => output::print_json(
+            "project",
+            &ProjectDocument {
+                path: &args.path,
+                editor_version: &version,
+                template: args.template.as_deref(),
+            },
+        )?,
+        OutputFormat::Tsv => output::print_tsv([version.to_string(), args.path.display().to_string()]),
+        OutputFormat::Text => {}
+    }
     Ok(0)
 }
 
 fn get_or_install_editor(project_path: impl AsRef<Path>, modules: Option<Vec<String>>, arch: Option<String>) -> Result<PathBuf, Box<dyn Error>> {
     let editor_version = wrum_lib::projects::get_project_editor_version(project_path)?;
-    install_editor(&editor_version, modules, arch.clone(), true)?;
+    install_editor(&editor_version, None, modules, arch.clone(), true)?;
     let editor_path = wrum_lib::editors::get_installed_editor_path(&editor_version, arch)?;
     if editor_path.is_none() {
         return Err("Something went wrong. Failed to install and obtain an editor".into());
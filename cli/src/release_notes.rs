@@ -0,0 +1,64 @@
+use crate::install::resolve_version;
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
+use clap::Args;
+use serde::Serialize;
+use std::error::Error;
+use wrum_lib::release_notes;
+use wrum_lib::version::selector::VersionSelector;
+use wrum_lib::version::UnityVersion;
+
+#[derive(Debug, Args)]
+pub struct ReleaseNotesArgs {
+    ///version to print the release notes of (e.g. 2022.3.10f1, 2022.3 or lts)
+    #[clap(required_unless_present = "diff")]
+    version: Option<VersionSelector>,
+    ///print the release notes of every release after FROM up to and including TO
+    #[clap(long, num_args = 2, value_names = ["FROM", "TO"], conflicts_with = "version")]
+    diff: Option<Vec<VersionSelector>>,
+    ///only print the lines matching the pattern (a regular expression)
+    #[clap(long, short)]
+    grep: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ReleaseNotesMatch<'a> {
+    version: &'a UnityVersion,
+    line: &'a str,
+}
+
+pub fn execute(args: ReleaseNotesArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let notes = match (args.version, args.diff) {
+        (Some(version), _) => vec![release_notes::get_release_notes(&resolve_version(&version)?)?],
+        (None, Some(range)) => release_notes::get_release_notes_between(&resolve_version(&range[0])?, &resolve_version(&range[1])?)?,
+        (None, None) => return Err("Specify a version or a --diff range".into()),
+    };
+    if let Some(pattern) = args.grep {
+        let matches = release_notes::search_release_notes(&notes, &pattern)?;
+        match global_opt.output {
+            OutputFormat::Json => {
+                let matches: Vec<ReleaseNotesMatch> = matches.into_iter().map(|(version, line)| ReleaseNotesMatch { version, line: line.trim() }).collect();
+                output::print_json("releaseNotesMatches", &matches)?;
+            }
+            OutputFormat::Tsv => matches.into_iter().for_each(|(version, line)| output::print_tsv([version.to_string().as_str(), line.trim()])),
+            OutputFormat::Text => matches.into_iter().for_each(|(version, line)| println!("{}: {}", version, line.trim())),
+        }
+        return Ok(0);
+    }
+    match global_opt.output {
+        OutputFormat::Json => return output::print_json("releaseNotes", &notes).map(|_| 0),
+        OutputFormat::Tsv => {
+            notes.iter().for_each(|release_notes| output::print_tsv([release_notes.version.to_string().as_str(), &release_notes.text]));
+            return Ok(0);
+        }
+        OutputFormat::Text => {}
+    }
+    let print_headers = notes.len() > 1;
+    for release_notes in notes {
+        if print_headers {
+            println!("== {} ==", release_notes.version);
+        }
+        println!("{}", release_notes.text);
+    }
+    Ok(0)
+}
@@ -0,0 +1,84 @@
+use crate::output::{OutputFormat, ReleaseOutput};
+use crate::{output, GlobalOpts};
+use clap::Args;
+use std::error::Error;
+use wrum_lib::editors::info::SystemArch;
+use wrum_lib::live_api::release_list::{UnityReleaseDownloadPlatform, UnityReleaseOrder};
+use wrum_lib::live_api::{release_list, ReleaseFilter};
+use wrum_lib::{live_api, system_info};
+
+#[derive(Debug, Args)]
+pub struct ReleasesArgs {
+    ///release streams to list. You can specify multiple values, separated by spaces.
+    #[clap(long, short, num_args = 1.., value_parser = ["lts", "beta", "alpha", "tech"])]
+    stream: Vec<String>,
+    ///only list releases matching the version text (e.g. 2022.3 or 6000.1)
+    #[clap(long, short)]
+    version: Option<String>,
+    ///download platform of the releases (linux, macos or windows), defaults to the current platform
+    #[clap(long, short, value_parser = ["linux", "macos", "windows"])]
+    platform: Option<String>,
+    ///editor architecture of the releases (x86_64 or arm64)
+    #[clap(long, short)]
+    architecture: Option<String>,
+    ///entitlements required by the releases (e.g. xlts)
+    #[clap(long, num_args = 1..)]
+    entitlement: Vec<String>,
+    ///maximum number of releases to list
+    #[clap(long, short, default_value_t = 25)]
+    limit: usize,
+    ///list every release matching the filters, ignoring --limit
+    #[clap(long, default_value_t = false)]
+    all: bool,
+    ///list the oldest releases first
+    #[clap(long, default_value_t = false)]
+    oldest_first: bool,
+}
+
+pub fn execute(args: ReleasesArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let platform = match args.platform {
+        None => UnityReleaseDownloadPlatform::from(system_info::get_platform()),
+        Some(platform) => UnityReleaseDownloadPlatform::from(platform),
+    };
+    let filter = ReleaseFilter {
+        stream: args.stream.into_iter().map(Into::into).collect(),
+        platform: vec![platform],
+        architecture: args.architecture.into_iter().map(SystemArch::from).collect(),
+        entitlements: args.entitlement.into_iter().map(Into::into).collect(),
+        version: args.version,
+        order: match args.oldest_first {
+            true => UnityReleaseOrder::RELEASE_DATE_ASC,
+            false => UnityReleaseOrder::RELEASE_DATE_DESC,
+        },
+        limit: match args.all {
+            true => None,
+            false => Some(args.limit),
+        },
+    };
+    let releases = live_api::get_release_list(&filter)?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("releases", &releases.iter().map(ReleaseOutput::from).collect::<Vec<_>>())?,
+        OutputFormat::Tsv => releases.into_iter().for_each(print_release_tsv),
+        OutputFormat::Text => releases.into_iter().for_each(print_release),
+    }
+    Ok(0)
+}
+
+fn print_release(release: release_list::ReleaseListItem) {
+    let release_date = release.release_date.get(..10).unwrap_or(&release.release_date);
+    let stream = String::from(release.stream);
+    match release.recommended {
+        true => println!("{} ({}), released {}, revision {}, recommended", release.version, stream, release_date, release.short_revision),
+        false => println!("{} ({}), released {}, revision {}", release.version, stream, release_date, release.short_revision),
+    }
+}
+
+fn print_release_tsv(release: release_list::ReleaseListItem) {
+    output::print_tsv([
+        release.version,
+        String::from(release.stream),
+        release.release_date,
+        release.short_revision,
+        release.recommended.to_string(),
+    ]);
+}
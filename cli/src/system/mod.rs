@@ -1,5 +1,6 @@
 use std::ffi::OsStr;
 use std::io;
+use std::path::Path;
 use std::process::ExitStatus;
 use wrum_lib::editors;
 use wrum_lib::live_api::latest_major_releases::UnityReleaseDownloadArchitecture;
@@ -17,6 +18,10 @@ where
     os::call_hub_command(args)
 }
 
+pub fn register_link_handler(executable_path: impl AsRef<Path>) -> io::Result<()> {
+    os::register_link_handler(executable_path)
+}
+
 pub fn get_installed_arch_string(arch: editors::info::SystemArch) -> Option<String> {
     os::get_installed_arch_string(arch)
 }
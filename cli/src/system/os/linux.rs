@@ -1,10 +1,14 @@
 use std::ffi::OsStr;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
+use std::{env, fs};
 use wrum_lib::editors::info::SystemArch;
 use wrum_lib::live_api::latest_major_releases::UnityReleaseDownloadArchitecture;
 
 static HUB_PATH: &str = "unityhub";
+static LINK_HANDLER_FILE_NAME: &str = "wrum-unityhub.desktop";
+static LINK_MIME_TYPE: &str = "x-scheme-handler/unityhub";
 
 pub fn call_hub_command<I, S>(args: I) -> io::Result<ExitStatus>
 where
@@ -14,6 +18,33 @@ where
     Command::new(HUB_PATH).arg("--headless").args(args).status()
 }
 
+pub fn register_link_handler(executable_path: impl AsRef<Path>) -> io::Result<()> {
+    let applications_path = match env::var_os("XDG_DATA_HOME") {
+        Some(data_home) => PathBuf::from(data_home),
+        None => PathBuf::from(env::var_os("HOME").ok_or(io::Error::other("HOME is not set"))?).join(".local").join("share"),
+    }
+    .join("applications");
+    fs::create_dir_all(&applications_path)?;
+    fs::write(
+        applications_path.join(LINK_HANDLER_FILE_NAME),
+        format!(
+            "[Desktop Entry]\nName=wrum\nComment=Install Unity editors from Unity Hub links\nExec=\"{}\" link %u\nType=Application\nNoDisplay=true\nMimeType={};\n",
+            executable_path.as_ref().display(),
+            LINK_MIME_TYPE
+        ),
+    )?;
+    let status = Command::new("xdg-mime")
+        .arg("default")
+        .arg(LINK_HANDLER_FILE_NAME)
+        .arg(LINK_MIME_TYPE)
+        .status()
+        .map_err(|err| io::Error::new(err.kind(), format!("Couldn't run xdg-mime: {}", err)))?;
+    if !status.success() {
+        return Err(io::Error::other("xdg-mime couldn't register the link handler"));
+    }
+    Ok(())
+}
+
 pub fn get_installed_arch_string(_arch: SystemArch) -> Option<String> {
     None
 }
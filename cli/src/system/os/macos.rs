@@ -1,5 +1,6 @@
 use std::ffi::OsStr;
 use std::io;
+use std::path::Path;
 use std::process::{Command, ExitStatus};
 use wrum_lib::editors::info::SystemArch;
 use wrum_lib::live_api::latest_major_releases::UnityReleaseDownloadArchitecture;
@@ -16,6 +17,10 @@ where
     Command::new(HUB_PATH).arg("--").arg("--headless").args(args).status()
 }
 
+pub fn register_link_handler(_executable_path: impl AsRef<Path>) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Registering a link handler requires an application bundle on macOS"))
+}
+
 pub fn get_installed_arch_string(arch: SystemArch) -> Option<String> {
     match arch {
         SystemArch::X86_64 => Some(String::from(LABEL_INTEL_ARCH)),
@@ -1,5 +1,6 @@
 use std::ffi::OsStr;
 use std::io;
+use std::path::Path;
 use std::process::{Command, ExitStatus};
 
 static HUB_PATH: &str = "C:\\Program Files\\Unity Hub>Unity Hub.exe";
@@ -12,6 +13,10 @@ where
     Command::new(HUB_PATH).arg("--").arg("--headless").args(args).status()
 }
 
+pub fn register_link_handler(_executable_path: impl AsRef<Path>) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Registering a link handler isn't supported on Windows yet"))
+}
+
 pub fn get_arch_string(arch: SystemArch) -> Option<String> {
     None
 }
@@ -0,0 +1,35 @@
+use crate::install::resolve_version;
+use crate::output::{OutputFormat, TemplateOutput};
+use crate::{output, GlobalOpts};
+use clap::Args;
+use std::error::Error;
+use wrum_lib::templates;
+use wrum_lib::version::selector::VersionSelector;
+
+#[derive(Debug, Args)]
+pub struct TemplatesArgs {
+    ///editor version the templates should be compatible with (e.g. 2022.3.10f1, 2022.3 or lts) - required
+    #[clap(long, short)]
+    version: VersionSelector,
+}
+
+pub fn execute(args: TemplatesArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let version = resolve_version(&args.version)?;
+    let templates = templates::list_templates(&version)?;
+    if global_opt.output == OutputFormat::Json {
+        output::print_json("templates", &templates.iter().map(TemplateOutput::from).collect::<Vec<_>>())?;
+        return Ok(0);
+    }
+    for template in templates {
+        let template_version = template.versions.iter().find(|version| version.is_latest).or(template.versions.last());
+        if global_opt.output == OutputFormat::Tsv {
+            output::print_tsv([&template.package_name, template_version.map(|version| version.name.as_str()).unwrap_or_default(), &template.name]);
+            continue;
+        }
+        match template_version {
+            None => println!("{} - {}", template.package_name, template.name),
+            Some(template_version) => println!("{}@{} - {}", template.package_name, template_version.name, template.name),
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,39 @@
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
+use clap::Args;
+use serde::Serialize;
+use std::error::Error;
+use wrum_lib::version::UnityVersion;
+
+#[derive(Debug, Args)]
+pub struct UninstallArgs {
+    ///version of the editor to uninstall (e.g. 2019.1.11f1) - required
+    #[clap(long, short)]
+    version: UnityVersion,
+    ///editor architecture to uninstall (x86_64 or arm64), required when several architectures of the version are installed
+    #[clap(long, short)]
+    architecture: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UninstallDocument<'a> {
+    version: &'a UnityVersion,
+    architecture: Option<&'a str>,
+}
+
+pub fn execute(args: UninstallArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    wrum_lib::editors::uninstall_editor(&args.version, args.architecture.clone())?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json(
+            "uninstall",
+            &UninstallDocument {
+                version: &args.version,
+                architecture: args.architecture.as_deref(),
+            },
+        )?,
+        OutputFormat::Tsv => output::print_tsv([args.version.to_string(), args.architecture.unwrap_or_default()]),
+        OutputFormat::Text => {}
+    }
+    Ok(0)
+}
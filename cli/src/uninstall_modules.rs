@@ -0,0 +1,41 @@
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
+use clap::Args;
+use serde::Serialize;
+use std::error::Error;
+use wrum_lib::version::selector::VersionSelector;
+use wrum_lib::version::UnityVersion;
+
+#[derive(Debug, Args)]
+pub struct UninstallModulesArgs {
+    ///version of the editor to remove the module from (e.g. 2019.1.11f1, 2022.3 or ">=2022.3, <2022.4"), the newest matching installed editor is used - required
+    #[clap(long, short)]
+    version: VersionSelector,
+    ///the module id. Its installed child modules are removed too. You can specify multiple values, separated by spaces.
+    #[clap(long, short, num_args = 1.., required = true)]
+    module: Vec<String>,
+    ///editor architecture to remove the module from (x86_64 or arm64)
+    #[clap(long, short)]
+    architecture: Option<String>,
+}
+
+#[derive(Serialize)]
+struct UninstallModulesDocument<'a> {
+    version: &'a UnityVersion,
+    modules: Vec<String>,
+}
+
+pub fn execute(args: UninstallModulesArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let version = wrum_lib::editors::resolve_installed_version(&args.version)?;
+    let mut modules_in_args: Vec<String> = vec![];
+    for module in args.module {
+        modules_in_args.append(&mut module.split(' ').map(str::to_string).collect());
+    }
+    let modules = wrum_lib::modules::uninstall_modules(&version, modules_in_args, args.architecture)?;
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("uninstallModules", &UninstallModulesDocument { version: &version, modules })?,
+        OutputFormat::Tsv => modules.iter().for_each(|module| output::print_tsv([version.to_string().as_str(), module])),
+        OutputFormat::Text => {}
+    }
+    Ok(0)
+}
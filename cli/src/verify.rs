@@ -0,0 +1,74 @@
+use crate::output::OutputFormat;
+use crate::{output, GlobalOpts};
+use clap::Args;
+use std::error::Error;
+use wrum_lib::verify;
+use wrum_lib::verify::VerifyReport;
+use wrum_lib::version::UnityVersion;
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    ///version of the editor to verify (e.g. 2019.1.11f1) - required
+    #[clap(long, short)]
+    version: UnityVersion,
+    ///editor architecture to verify (x86_64 or arm64)
+    #[clap(long, short)]
+    architecture: Option<String>,
+    ///download and unpack again the editor and modules with missing or modified files
+    #[clap(long, default_value_t = false)]
+    repair: bool,
+}
+
+pub fn execute(args: VerifyArgs, global_opt: GlobalOpts) -> Result<i32, Box<dyn Error>> {
+    let mut report = verify::verify_editor(&args.version, args.architecture.clone())?;
+    if args.repair && report.is_broken() {
+        verify::repair_editor(&report)?;
+        report = verify::verify_editor(&args.version, args.architecture)?;
+    }
+    match global_opt.output {
+        OutputFormat::Json => output::print_json("verify", &report)?,
+        OutputFormat::Tsv => print_report_tsv(&report),
+        OutputFormat::Text => print_report(&report),
+    }
+    match report.is_broken() {
+        true => Ok(1),
+        false => Ok(0),
+    }
+}
+
+fn print_report(report: &VerifyReport) {
+    for component in &report.components {
+        if !component.tracked && !component.is_broken() {
+            println!("{}: unknown, its files weren't recorded at install time", component.id);
+            continue;
+        }
+        if !component.is_broken() {
+            println!("{}: ok", component.id);
+            continue;
+        }
+        println!("{}: {} missing, {} modified", component.id, component.missing.len(), component.modified.len());
+        for path in &component.missing {
+            println!("  missing {}", path.display());
+        }
+        for path in &component.modified {
+            println!("  modified {}", path.display());
+        }
+    }
+}
+
+fn print_report_tsv(report: &VerifyReport) {
+    for component in &report.components {
+        let status = match (component.tracked, component.is_broken()) {
+            (_, true) => "broken",
+            (false, false) => "unknown",
+            (true, false) => "ok",
+        };
+        output::print_tsv([component.id.as_str(), status, ""]);
+        for path in &component.missing {
+            output::print_tsv([component.id.as_str(), "missing", &path.display().to_string()]);
+        }
+        for path in &component.modified {
+            output::print_tsv([component.id.as_str(), "modified", &path.display().to_string()]);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::system_info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+static CONFIG_FILE_NAME: &str = "config.json";
+static API_URL_ENV: &str = "WRUM_API_URL";
+static API_FIXTURES_PATH_ENV: &str = "WRUM_API_FIXTURES_PATH";
+static DOWNLOAD_CACHE_PATH_ENV: &str = "WRUM_DOWNLOAD_CACHE_PATH";
+static DEFAULT_API_URL: &str = "https://live-platform-api.prd.ld.unity3d.com/graphql";
+static DEFAULT_DOWNLOAD_BASE_URL: &str = "https://download.unity3d.com/download_unity/";
+const DEFAULT_CACHE_TTL: u64 = 3600;
+const DEFAULT_API_TIMEOUT: u64 = 30;
+const DEFAULT_API_RETRIES: u32 = 3;
+const DEFAULT_DOWNLOAD_CONNECTIONS: u32 = 4;
+const DEFAULT_DOWNLOAD_CACHE_MAX_SIZE: u64 = 34359738368;
+
+static CONFIG: RwLock<Option<Config>> = RwLock::new(None);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+    pub api_url: String,
+    pub api_fixtures_path: Option<PathBuf>,
+    pub api_timeout: u64,
+    pub api_retries: u32,
+    pub user_agent: String,
+    pub cache_ttl: u64,
+    pub offline: bool,
+    #[serde(skip)]
+    pub refresh: bool,
+    #[serde(skip)]
+    pub skip_integrity: bool,
+    pub download_base_url: String,
+    pub download_connections: u32,
+    pub download_cache_path: Option<PathBuf>,
+    pub download_cache_max_size: u64,
+    pub workspace_roots: Vec<PathBuf>,
+    pub url_rewrites: Vec<UrlRewrite>,
+    pub http_headers: HashMap<String, HashMap<String, String>>,
+    pub proxy: Option<String>,
+    pub ca_bundle: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UrlRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            api_url: String::from(DEFAULT_API_URL),
+            api_fixtures_path: None,
+            api_timeout: DEFAULT_API_TIMEOUT,
+            api_retries: DEFAULT_API_RETRIES,
+            user_agent: format!("wrum/{}", env!("CARGO_PKG_VERSION")),
+            cache_ttl: DEFAULT_CACHE_TTL,
+            offline: false,
+            refresh: false,
+            skip_integrity: false,
+            download_base_url: String::from(DEFAULT_DOWNLOAD_BASE_URL),
+            download_connections: DEFAULT_DOWNLOAD_CONNECTIONS,
+            download_cache_path: None,
+            download_cache_max_size: DEFAULT_DOWNLOAD_CACHE_MAX_SIZE,
+            workspace_roots: Vec::new(),
+            url_rewrites: Vec::new(),
+            http_headers: HashMap::new(),
+            proxy: None,
+            ca_bundle: None,
+        }
+    }
+}
+
+pub fn get_config() -> Result<Config, Box<dyn Error>> {
+    if let Some(config) = CONFIG.read().unwrap().as_ref() {
+        return Ok(config.clone());
+    }
+    let config = load_config()?;
+    set_config(config.clone());
+    Ok(config)
+}
+
+pub fn set_config(config: Config) {
+    *CONFIG.write().unwrap() = Some(config);
+}
+
+pub fn get_config_file_path() -> PathBuf {
+    system_info::get_wrum_config_path().join(CONFIG_FILE_NAME)
+}
+
+fn load_config() -> Result<Config, Box<dyn Error>> {
+    let config_file_path = get_config_file_path();
+    let mut config = match config_file_path.exists() {
+        true => {
+            let contents = fs::read_to_string(&config_file_path)?;
+            serde_json::from_str(&contents).map_err(|err| format!("Couldn't read {}: {}", config_file_path.display(), err))?
+        }
+        false => Config::default(),
+    };
+    if let Ok(api_url) = env::var(API_URL_ENV) {
+        if !api_url.is_empty() {
+            config.api_url = api_url;
+        }
+    }
+    if let Ok(fixtures_path) = env::var(API_FIXTURES_PATH_ENV) {
+        if !fixtures_path.is_empty() {
+            config.api_fixtures_path = Some(PathBuf::from(fixtures_path));
+        }
+    }
+    if let Ok(download_cache_path) = env::var(DOWNLOAD_CACHE_PATH_ENV) {
+        if !download_cache_path.is_empty() {
+            config.download_cache_path = Some(PathBuf::from(download_cache_path));
+        }
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn environment_overrides_config_file() {
+        let _config = test_support::set_test_config(Config::default());
+        fs::write(get_config_file_path(), r#"{"apiUrl":"https://mirror.example.com/graphql","apiRetries":5}"#).unwrap();
+        env::set_var(API_FIXTURES_PATH_ENV, "/fixtures");
+        let config = load_config();
+        env::set_var(API_URL_ENV, "http://127.0.0.1:8080/graphql");
+        let overridden_config = load_config();
+        env::remove_var(API_FIXTURES_PATH_ENV);
+        env::remove_var(API_URL_ENV);
+        fs::remove_file(get_config_file_path()).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.api_url, "https://mirror.example.com/graphql");
+        assert_eq!(config.api_retries, 5);
+        assert_eq!(config.api_fixtures_path, Some(PathBuf::from("/fixtures")));
+        assert_eq!(overridden_config.unwrap().api_url, "http://127.0.0.1:8080/graphql");
+    }
+}
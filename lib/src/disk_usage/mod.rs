@@ -0,0 +1,357 @@
+use crate::editors::info::EditorInfo;
+use crate::modules::manifest;
+use crate::version::UnityVersion;
+use crate::{config, editors, install, modules, progress, projects, system_info};
+use fs4::fs_std::FileExt;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+static DOWNLOADS_FOLDER_NAME: &str = "downloads";
+static PLAYBACK_ENGINES_FOLDER_NAME: &str = "PlaybackEngines";
+static SKIPPED_PROJECT_FOLDERS: [&str; 5] = ["Library", "Temp", "Logs", "obj", "node_modules"];
+const PROJECT_SEARCH_DEPTH: usize = 6;
+
+#[derive(Serialize, Clone)]
+pub struct FileUsage {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleUsage {
+    pub id: String,
+    pub size: u64,
+    pub tracked: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorUsage {
+    #[serde(flatten)]
+    pub editor: EditorInfo,
+    pub path: PathBuf,
+    pub total_size: u64,
+    pub modules: Vec<ModuleUsage>,
+    pub downloads: Vec<FileUsage>,
+    pub stale_locks: Vec<PathBuf>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupPlan {
+    pub downloads: Vec<FileUsage>,
+    pub stale_locks: Vec<PathBuf>,
+    pub unused_editors: Vec<EditorUsage>,
+    pub referenced_versions: Vec<UnityVersion>,
+    /// Folders and projects of the workspace roots that couldn't be read. Any of them could use an editor, so none is removed while they exist.
+    pub unreadable_paths: Vec<PathBuf>,
+}
+
+impl CleanupPlan {
+    pub fn is_empty(&self) -> bool {
+        self.downloads.is_empty() && self.stale_locks.is_empty() && self.unused_editors.is_empty()
+    }
+
+    pub fn reclaimable_size(&self) -> u64 {
+        let downloads_size: u64 = self.downloads.iter().map(|download| download.size).sum();
+        let editors_size: u64 = self.unused_editors.iter().map(|editor| editor.total_size).sum();
+        downloads_size + editors_size
+    }
+}
+
+pub fn get_editors_usage() -> Result<Vec<EditorUsage>, Box<dyn Error>> {
+    let mut usages = Vec::new();
+    for editor in editors::list_installed_editors()? {
+        usages.push(get_editor_usage(editor)?);
+    }
+    Ok(usages)
+}
+
+pub fn get_editor_usage(editor: EditorInfo) -> Result<EditorUsage, Box<dyn Error>> {
+    let path = editor.path.clone();
+    let mut downloads = Vec::new();
+    let downloads_path = path.join(DOWNLOADS_FOLDER_NAME);
+    if downloads_path.is_dir() {
+        for child in fs::read_dir(&downloads_path)? {
+            let download_path = child?.path();
+            if download_path.is_file() {
+                downloads.push(FileUsage { size: fs::metadata(&download_path)?.len(), path: download_path });
+            }
+        }
+    }
+    Ok(EditorUsage {
+        total_size: get_folder_size(&path),
+        modules: get_modules_usage(&editor)?,
+        stale_locks: get_stale_locks(&path)?,
+        downloads,
+        path,
+        editor,
+    })
+}
+
+/// Lists the editors, downloads and lock files that can be removed. Editors are only considered unused when workspace roots are given,
+/// every project in them could be read and wrum installed the editor.
+pub fn plan_cleanup(workspace_roots: Option<Vec<PathBuf>>) -> Result<CleanupPlan, Box<dyn Error>> {
+    let workspace_roots = match workspace_roots {
+        Some(workspace_roots) => workspace_roots,
+        None => config::get_config()?.workspace_roots,
+    };
+    let mut unreadable_paths = Vec::new();
+    let mut referenced_versions = Vec::new();
+    for project_path in find_projects(&workspace_roots, &mut unreadable_paths)? {
+        match projects::get_project_editor_version(&project_path) {
+            Ok(version) => referenced_versions.push(version),
+            Err(err) => {
+                progress::warn(format!("couldn't read the editor version of {}: {}", project_path.display(), err));
+                unreadable_paths.push(project_path);
+            }
+        }
+    }
+    referenced_versions.sort();
+    referenced_versions.dedup();
+    if !unreadable_paths.is_empty() {
+        progress::warn(String::from("keeping every editor, some projects of the workspace roots couldn't be read"));
+    }
+
+    let can_remove_editors = !workspace_roots.is_empty() && unreadable_paths.is_empty();
+    let mut plan = CleanupPlan {
+        downloads: Vec::new(),
+        stale_locks: Vec::new(),
+        unused_editors: Vec::new(),
+        referenced_versions,
+        unreadable_paths,
+    };
+    for usage in get_editors_usage()? {
+        if can_remove_editors && usage.editor.is_installed_by_wrum() && !plan.referenced_versions.contains(&usage.editor.version) {
+            plan.unused_editors.push(usage);
+            continue;
+        }
+        plan.downloads.extend(usage.downloads);
+        plan.stale_locks.extend(usage.stale_locks);
+    }
+    Ok(plan)
+}
+
+/// Removes everything in the plan and returns the number of bytes freed. Items another process started using since the plan was made are skipped.
+pub fn clean_up(plan: &CleanupPlan) -> Result<u64, Box<dyn Error>> {
+    let mut freed_size = 0;
+    for download in &plan.downloads {
+        let editor_path = download.path.parent().and_then(Path::parent).ok_or("Invalid download path")?;
+        let lock_files = match install::try_get_install_locks(editor_path)? {
+            None => {
+                progress::warn(format!("skipping {}, it is being downloaded by another process", download.path.display()));
+                continue;
+            }
+            Some(lock_files) => lock_files,
+        };
+        if download.path.exists() {
+            fs::remove_file(&download.path)?;
+            freed_size += download.size;
+        }
+        for lock_file in lock_files {
+            install::release_install_lock(lock_file)?;
+        }
+    }
+    for lock_path in &plan.stale_locks {
+        let lock_file = match File::open(lock_path) {
+            Err(_) => continue,
+            Ok(lock_file) => lock_file,
+        };
+        if lock_file.try_lock_exclusive().is_err() {
+            progress::warn(format!("skipping {}, it is held by another process", lock_path.display()));
+            continue;
+        }
+        // Removed while still held, so no install can take the lock in between.
+        fs::remove_file(lock_path)?;
+        install::release_install_lock(lock_file)?;
+    }
+    for usage in &plan.unused_editors {
+        match editors::uninstall_editor(&usage.editor.version, Some(String::from(usage.editor.arch.clone()))) {
+            Ok(_) => freed_size += usage.total_size,
+            Err(err) => progress::warn(format!("couldn't uninstall {}: {}", usage.editor.version, err)),
+        }
+    }
+    Ok(freed_size)
+}
+
+fn get_modules_usage(editor: &EditorInfo) -> Result<Vec<ModuleUsage>, Box<dyn Error>> {
+    let mut usages = Vec::new();
+    if editor.path.join("modules.json").exists() {
+        for module in modules::load_modules_from_disk(&editor.path)?.into_iter().filter(|module| module.selected) {
+            if let Some(module_manifest) = manifest::read_manifest(&editor.path, &module.id)? {
+                usages.push(ModuleUsage {
+                    id: module.id,
+                    size: module_manifest.files.iter().map(|file| file.size).sum(),
+                    tracked: true,
+                });
+            }
+        }
+    }
+    if usages.is_empty() {
+        usages = get_playback_engines_usage(&editor.path)?;
+    }
+    usages.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(usages)
+}
+
+fn get_playback_engines_usage(editor_path: &Path) -> Result<Vec<ModuleUsage>, Box<dyn Error>> {
+    let mut usages = Vec::new();
+    let roots = [system_info::get_editor_data_path(editor_path).join(PLAYBACK_ENGINES_FOLDER_NAME), editor_path.join(PLAYBACK_ENGINES_FOLDER_NAME)];
+    for root in roots.iter().filter(|root| root.is_dir()) {
+        for child in fs::read_dir(root)? {
+            let engine_path = child?.path();
+            if !engine_path.is_dir() {
+                continue;
+            }
+            usages.push(ModuleUsage {
+                id: engine_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                size: get_folder_size(&engine_path),
+                tracked: false,
+            });
+        }
+    }
+    Ok(usages)
+}
+
+fn get_stale_locks(editor_path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut stale_locks = Vec::new();
+    for child in fs::read_dir(editor_path)? {
+        let lock_path = child?.path();
+        let file_name = lock_path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if !file_name.starts_with('.') || !file_name.ends_with(".lock") {
+            continue;
+        }
+        let lock_file = File::open(&lock_path)?;
+        if lock_file.try_lock_exclusive().is_ok() {
+            install::release_install_lock(lock_file)?;
+            stale_locks.push(lock_path);
+        }
+    }
+    stale_locks.sort();
+    Ok(stale_locks)
+}
+
+fn find_projects(workspace_roots: &[PathBuf], unreadable_paths: &mut Vec<PathBuf>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut project_paths = Vec::new();
+    for root in workspace_roots {
+        if let Err(err) = fs::read_dir(root) {
+            return Err(format!("Couldn't read the workspace root {}: {}", root.display(), err).into());
+        }
+        let entries = WalkDir::new(root)
+            .max_depth(PROJECT_SEARCH_DEPTH)
+            .into_iter()
+            .filter_entry(|entry| {
+                let file_name = entry.file_name().to_string_lossy();
+                entry.depth() == 0 || (!file_name.starts_with('.') && !SKIPPED_PROJECT_FOLDERS.contains(&file_name.as_ref()))
+            });
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    progress::warn(format!("couldn't search the workspace root {}: {}", root.display(), err));
+                    unreadable_paths.push(err.path().unwrap_or(root).to_path_buf());
+                    continue;
+                }
+            };
+            if entry.file_type().is_dir() && entry.path().join("ProjectSettings").join("ProjectVersion.txt").is_file() {
+                project_paths.push(entry.into_path());
+            }
+        }
+    }
+    Ok(project_paths)
+}
+
+pub(crate) fn get_folder_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_type().is_dir())
+        .filter_map(|entry| entry.path().symlink_metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use tempfile::TempDir;
+
+    fn create_editor(version: &str, wrum_json: &str) -> PathBuf {
+        let editor_path = system_info::get_default_install_path().join(version);
+        let executable_path = system_info::get_editor_executable_path(&editor_path);
+        fs::create_dir_all(executable_path.parent().unwrap()).unwrap();
+        fs::write(executable_path, "").unwrap();
+        fs::write(editor_path.join("wrum.json"), wrum_json).unwrap();
+        editor_path
+    }
+
+    fn create_project(root: &Path, name: &str, project_version: &str) {
+        let settings_path = root.join(name).join("ProjectSettings");
+        fs::create_dir_all(&settings_path).unwrap();
+        fs::write(settings_path.join("ProjectVersion.txt"), project_version).unwrap();
+    }
+
+    fn get_unused_versions(plan: &CleanupPlan) -> Vec<String> {
+        plan.unused_editors.iter().map(|usage| usage.editor.version.to_string()).collect()
+    }
+
+    #[test]
+    fn only_unreferenced_editors_installed_by_wrum_are_planned() {
+        let _config = test_support::set_test_config(Config::default());
+        let installed = create_editor("2019.4.1f1", r#"{"version":"2019.4.1f1","arch":"X86_64","downloadType":"TAR_XZ"}"#);
+        let added = create_editor("2019.4.2f1", r#"{"version":"2019.4.2f1","arch":"X86_64"}"#);
+        let workspace = TempDir::new().unwrap();
+        create_project(workspace.path(), "Game", "m_EditorVersion: 6000.0.1f1\n");
+
+        let plan = plan_cleanup(Some(vec![workspace.path().to_path_buf()])).unwrap();
+        assert_eq!(get_unused_versions(&plan), ["2019.4.1f1"]);
+        assert!(plan.unreadable_paths.is_empty());
+
+        create_project(workspace.path(), "Broken", "m_EditorVersion: unknown\n");
+        let plan = plan_cleanup(Some(vec![workspace.path().to_path_buf()])).unwrap();
+        assert!(plan.unused_editors.is_empty());
+        assert_eq!(plan.unreadable_paths, [workspace.path().join("Broken")]);
+
+        fs::remove_dir_all(installed).unwrap();
+        fs::remove_dir_all(added).unwrap();
+    }
+
+    #[test]
+    fn missing_workspace_roots_are_errors() {
+        let _config = test_support::set_test_config(Config::default());
+        let workspace = TempDir::new().unwrap();
+
+        let err = plan_cleanup(Some(vec![workspace.path().join("missing")])).err().unwrap();
+        assert!(err.to_string().contains("Couldn't read the workspace root"), "{}", err);
+    }
+
+    #[test]
+    fn stale_locks_are_removed_and_held_locks_kept() {
+        let _config = test_support::set_test_config(Config::default());
+        let editor_path = TempDir::new().unwrap();
+        let held_lock = install::get_install_lock("Android", editor_path.path()).unwrap();
+        let stale_lock_path = editor_path.path().join(".iOS.lock");
+        fs::write(&stale_lock_path, "").unwrap();
+        let plan = CleanupPlan {
+            downloads: Vec::new(),
+            stale_locks: get_stale_locks(editor_path.path()).unwrap(),
+            unused_editors: Vec::new(),
+            referenced_versions: Vec::new(),
+            unreadable_paths: Vec::new(),
+        };
+        assert_eq!(plan.stale_locks, std::slice::from_ref(&stale_lock_path));
+
+        let plan = CleanupPlan { stale_locks: vec![stale_lock_path.clone(), editor_path.path().join(".Android.lock")], ..plan };
+        clean_up(&plan).unwrap();
+        assert!(!stale_lock_path.exists());
+        assert!(editor_path.path().join(".Android.lock").exists());
+        install::release_install_lock(held_lock).unwrap();
+    }
+}
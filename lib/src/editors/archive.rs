@@ -0,0 +1,246 @@
+use crate::editors::info::SystemArch;
+use crate::editors::EditorDownload;
+use crate::live_api::release_info::FileType;
+use crate::modules::info::ModuleInfo;
+use crate::modules::info::SizeUnitType::Value;
+use crate::version::UnityVersion;
+use crate::{config, network, system_info};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+
+static EDITOR_SECTION: &str = "Unity";
+// Compressed editor and module archives unpack to roughly this many times their size.
+const LOCAL_ARCHIVE_EXPANSION_RATIO: u64 = 4;
+
+type IniSections = Vec<(String, HashMap<String, String>)>;
+
+pub fn get_archive_download(version: &UnityVersion, changeset: &str, arch: SystemArch) -> Result<EditorDownload, Box<dyn Error>> {
+    let base_url = get_changeset_url(changeset)?;
+    let ini_url = format!("{}unity-{}-{}.ini", base_url, version, system_info::get_archive_ini_platform(arch.clone()));
+    let response = network::get(&ini_url)?;
+    if !response.status().is_success() {
+        return Err(format!("Couldn't find release {} ({}) in the download archive", version, changeset).into());
+    }
+    let sections = parse_ini(&response.text()?);
+
+    let (_, editor_section) = sections
+        .iter()
+        .find(|(name, _)| name == EDITOR_SECTION)
+        .ok_or("Download archive manifest doesn't describe the editor")?;
+    let editor_url = editor_section.get("url").ok_or("Download archive manifest doesn't include the editor url")?;
+    let modules = sections
+        .iter()
+        .filter(|(name, section)| name != EDITOR_SECTION && section.contains_key("url"))
+        .map(|(name, section)| convert_archive_module(name, section, &base_url))
+        .collect();
+
+    Ok(EditorDownload {
+        url: get_archive_url(&base_url, editor_url),
+        integrity: None,
+        file_type: get_file_type(editor_url),
+        architecture: arch,
+        download_size: get_size(editor_section, "size"),
+        installed_size: get_size(editor_section, "installedsize"),
+        modules,
+    })
+}
+
+fn convert_archive_module(name: &str, section: &HashMap<String, String>, base_url: &str) -> ModuleInfo {
+    let url = get_archive_url(base_url, &section["url"]);
+    let get_value = |key: &str| section.get(key).cloned().unwrap_or_default();
+    let get_flag = |key: &str| section.get(key).is_some_and(|value| value == "true");
+    let sync = get_value("sync").to_lowercase();
+    ModuleInfo {
+        url: url.clone(),
+        integrity: None,
+        module_type: get_file_type(&url),
+        id: name.to_lowercase(),
+        name: section.get("title").cloned().unwrap_or(String::from(name)),
+        slug: name.to_lowercase(),
+        description: get_value("description"),
+        category: String::new(),
+        download_size: Value(get_size(section, "size")),
+        installed_size: Value(get_size(section, "installedsize")),
+        required: get_flag("mandatory"),
+        hidden: get_flag("hidden"),
+        extracted_path_rename: None,
+        pre_selected: get_flag("install"),
+        destination: Some(system_info::get_archive_module_destination(&url)),
+        submodules: None,
+        __typename: String::from("UnityReleaseModule"),
+        download_url: url,
+        visible: !get_flag("hidden"),
+        selected: false,
+        sync: sync.clone(),
+        parent: sync,
+        eula_url_1: get_value("eulaurl1"),
+        eula_label_1: get_value("eulalabel1"),
+        eula_message: get_value("eulamessage"),
+        rename_to: get_value("renameTo"),
+        rename_from: get_value("renameFrom"),
+        preselected: get_flag("install"),
+    }
+}
+
+pub(crate) fn get_local_archive_module(id: &str, archive_path: &Path) -> ModuleInfo {
+    let archive_path = archive_path.to_string_lossy().to_string();
+    let mut module = convert_archive_module(id, &HashMap::from([(String::from("url"), archive_path.clone())]), "");
+    module.url = archive_path.clone();
+    module.download_url = archive_path;
+    module
+}
+
+/// Estimates the space a local archive takes once unpacked. Zip archives list the exact size of
+/// their files, the other formats are assumed to expand by a fixed ratio.
+pub(crate) fn get_local_archive_installed_size(archive_path: &Path) -> Result<u64, Box<dyn Error>> {
+    if let FileType::ZIP = get_file_type(&archive_path.to_string_lossy()) {
+        let mut archive = zip::ZipArchive::new(File::open(archive_path)?)?;
+        let mut installed_size = 0;
+        for index in 0..archive.len() {
+            installed_size += archive.by_index_raw(index)?.size();
+        }
+        return Ok(installed_size);
+    }
+    Ok(fs::metadata(archive_path)?.len().saturating_mul(LOCAL_ARCHIVE_EXPANSION_RATIO))
+}
+
+fn get_changeset_url(changeset: &str) -> Result<String, Box<dyn Error>> {
+    let mut base_url = config::get_config()?.download_base_url;
+    if !base_url.ends_with('/') {
+        base_url.push('/');
+    }
+    Ok(format!("{}{}/", base_url, changeset))
+}
+
+fn get_archive_url(base_url: &str, url: &str) -> String {
+    match url.starts_with("http://") || url.starts_with("https://") {
+        true => String::from(url),
+        false => format!("{}{}", base_url, url.trim_start_matches('/')),
+    }
+}
+
+fn get_size(section: &HashMap<String, String>, key: &str) -> f64 {
+    section.get(key).and_then(|value| value.parse::<f64>().ok()).unwrap_or(0.0) * 1024.0
+}
+
+pub(crate) fn get_file_type(url: &str) -> FileType {
+    let url = url.to_lowercase();
+    match url.rsplit('.').next() {
+        Some("xz") => FileType::TAR_XZ,
+        Some("gz") => FileType::TAR_GZ,
+        Some("zip") => FileType::ZIP,
+        Some("pkg") => FileType::PKG,
+        Some("exe") => FileType::EXE,
+        Some("po") => FileType::PO,
+        Some("dmg") => FileType::DMG,
+        _ => FileType::Other(String::new()),
+    }
+}
+
+fn parse_ini(contents: &str) -> IniSections {
+    let mut sections: IniSections = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            sections.push((String::from(name), HashMap::new()));
+        } else if let (Some((key, value)), Some((_, section))) = (line.split_once('='), sections.last_mut()) {
+            section.insert(String::from(key.trim()), String::from(value.trim()));
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use std::str::FromStr;
+
+    static MANIFEST: &str = "
+[Unity]
+title=Unity 2022.3.10f1
+url=Editor/Unity-2022.3.10f1.tar.xz
+size=1024
+installedsize=4096
+
+; modules
+[Android]
+title=Android Build Support
+description=Allows building your Unity projects for the Android platform
+url=TargetSupportInstaller/UnitySetup-Android-Support-for-Editor-2022.3.10f1.tar.xz
+size=512
+installedsize=2048
+install=true
+
+[Android-SDK-NDK-Tools]
+title=Android SDK & NDK Tools
+url=https://dl.google.com/android/repository/commandlinetools.zip
+sync=Android
+hidden=true
+
+[Documentation]
+title=Documentation
+";
+
+    fn start_archive_server() -> TestServer {
+        let manifest_path = format!("/abcdef123456/unity-2022.3.10f1-{}.ini", system_info::get_archive_ini_platform(SystemArch::X86_64));
+        TestServer::start(move |request| match request.path == manifest_path {
+            true => TestResponse::new(200, MANIFEST),
+            false => TestResponse::new(404, "Not Found"),
+        })
+    }
+
+    #[test]
+    fn reads_the_changeset_manifest() {
+        let server = start_archive_server();
+        let _config = test_support::set_test_config(Config { download_base_url: server.url("/"), ..Config::default() });
+
+        let download = get_archive_download(&UnityVersion::from_str("2022.3.10f1").unwrap(), "abcdef123456", SystemArch::X86_64).unwrap();
+        assert_eq!(download.url, server.url("/abcdef123456/Editor/Unity-2022.3.10f1.tar.xz"));
+        assert!(matches!(download.file_type, FileType::TAR_XZ));
+        assert_eq!(download.download_size, 1024.0 * 1024.0);
+        assert_eq!(download.installed_size, 4096.0 * 1024.0);
+
+        let ids: Vec<&str> = download.modules.iter().map(|module| module.id.as_str()).collect();
+        assert_eq!(ids, ["android", "android-sdk-ndk-tools"]);
+        let android = &download.modules[0];
+        assert_eq!(android.url, server.url("/abcdef123456/TargetSupportInstaller/UnitySetup-Android-Support-for-Editor-2022.3.10f1.tar.xz"));
+        assert!(android.pre_selected && android.visible);
+        let tools = &download.modules[1];
+        assert_eq!(tools.url, "https://dl.google.com/android/repository/commandlinetools.zip");
+        assert!(matches!(tools.module_type, FileType::ZIP));
+        assert_eq!((tools.sync.as_str(), tools.parent.as_str()), ("android", "android"));
+        assert!(tools.hidden && !tools.visible);
+    }
+
+    #[test]
+    fn unknown_changeset_is_an_error() {
+        let server = start_archive_server();
+        let _config = test_support::set_test_config(Config { download_base_url: server.url("/"), ..Config::default() });
+
+        let err = get_archive_download(&UnityVersion::from_str("2022.3.10f1").unwrap(), "000000000000", SystemArch::X86_64).err().unwrap();
+        assert_eq!(err.to_string(), "Couldn't find release 2022.3.10f1 (000000000000) in the download archive");
+    }
+
+    #[test]
+    fn local_archive_installed_size() {
+        let folder = tempfile::TempDir::new().unwrap();
+        let zip_path = folder.path().join("module.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        writer.start_file("data.bin", zip::write::SimpleFileOptions::default()).unwrap();
+        std::io::Write::write_all(&mut writer, &[0; 10000]).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(get_local_archive_installed_size(&zip_path).unwrap(), 10000);
+
+        let tar_path = folder.path().join("Unity.tar.xz");
+        fs::write(&tar_path, [0; 100]).unwrap();
+        assert_eq!(get_local_archive_installed_size(&tar_path).unwrap(), 100 * LOCAL_ARCHIVE_EXPANSION_RATIO);
+    }
+}
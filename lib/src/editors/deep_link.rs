@@ -0,0 +1,83 @@
+use crate::version::UnityVersion;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+static HUB_LINK_SCHEME: &str = "unityhub://";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HubDeepLink {
+    pub version: UnityVersion,
+    pub changeset: String,
+}
+
+impl FromStr for HubDeepLink {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_link = || format!("Invalid Unity Hub link \"{}\", expected unityhub://<version>/<changeset>", value);
+        let link = value.trim();
+        let link = match link.get(..HUB_LINK_SCHEME.len()) {
+            Some(scheme) if scheme.eq_ignore_ascii_case(HUB_LINK_SCHEME) => &link[HUB_LINK_SCHEME.len()..],
+            _ => return Err(invalid_link()),
+        };
+        let link = link.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+        let (version, changeset) = link.split_once('/').ok_or_else(invalid_link)?;
+        if changeset.is_empty() || !changeset.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid_link());
+        }
+        Ok(HubDeepLink {
+            version: version.parse()?,
+            changeset: changeset.to_lowercase(),
+        })
+    }
+}
+
+impl Display for HubDeepLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", HUB_LINK_SCHEME, self.version, self.changeset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<HubDeepLink, String> {
+        value.parse()
+    }
+
+    #[test]
+    fn parses_hub_links() {
+        let expected = HubDeepLink { version: "2022.3.10f1".parse().unwrap(), changeset: String::from("ff3792e53c62") };
+        for value in [
+            "unityhub://2022.3.10f1/ff3792e53c62",
+            "UnityHub://2022.3.10f1/FF3792E53C62",
+            "  unityhub://2022.3.10f1/ff3792e53c62/  ",
+            "unityhub://2022.3.10f1/ff3792e53c62?module=android",
+            "unityhub://2022.3.10f1/ff3792e53c62#download",
+        ] {
+            assert_eq!(parse(value), Ok(expected.clone()), "{}", value);
+        }
+        assert_eq!(expected.to_string(), "unityhub://2022.3.10f1/ff3792e53c62");
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        for value in [
+            "",
+            "https://2022.3.10f1/ff3792e53c62",
+            "unityhub:/2022.3.10f1/ff3792e53c62",
+            "unityhub://2022.3.10f1",
+            "unityhub://2022.3.10f1/",
+            "unityhub://2022.3.10f1/?module=android",
+            "unityhub://2022.3.10f1/ff3792e53c6z",
+            "unityhub://2022.3.10f1/ff37/92e5",
+            "unityhub://2022.3/ff3792e53c62",
+            "unityhub://ff3792e53c62",
+            "unityhübb://2022.3.10f1/ff3792e53c62",
+        ] {
+            assert!(parse(value).is_err(), "{} should be rejected", value);
+        }
+    }
+}
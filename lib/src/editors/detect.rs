@@ -0,0 +1,156 @@
+use crate::system_info;
+use crate::version::UnityVersion;
+use regex::Regex;
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+const LAUNCH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+static PLIST_VERSION_KEYS: [&str; 3] = ["CFBundleVersion", "CFBundleShortVersionString", "CFBundleGetInfoString"];
+
+/// Files shipped with the editor are trusted before the folder name, which users are free to rename.
+/// The editor is only launched when none of them holds a version.
+pub fn detect_editor_version(editor_path: &Path, executable_path: &Path) -> Result<UnityVersion, Box<dyn Error>> {
+    let detectors: [fn(&Path) -> Option<UnityVersion>; 4] = [read_info_plist_version, read_ivy_version, read_modules_version, read_folder_name_version];
+    if let Some(version) = detectors.iter().find_map(|detect| detect(editor_path)) {
+        return Ok(version);
+    }
+    launch_editor_for_version(executable_path)
+}
+
+fn read_info_plist_version(editor_path: &Path) -> Option<UnityVersion> {
+    let contents = fs::read_to_string(system_info::get_editor_data_path(editor_path).join("Info.plist")).ok()?;
+    static REGEXES: OnceLock<Vec<Regex>> = OnceLock::new();
+    let regexes = REGEXES.get_or_init(|| {
+        PLIST_VERSION_KEYS.iter().map(|key| Regex::new(&format!(r"<key>{}</key>\s*<string>([^<]*)</string>", key)).unwrap()).collect()
+    });
+    regexes.iter().find_map(|regex| UnityVersion::find_in(&regex.captures(&contents)?[1]))
+}
+
+fn read_ivy_version(editor_path: &Path) -> Option<UnityVersion> {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = REGEX.get_or_init(|| Regex::new(r#"unityVersion="([^"]+)""#).unwrap());
+    let data_path = system_info::get_editor_data_path(editor_path);
+    let roots = [data_path.join("UnityExtensions").join("Unity"), data_path.join("PlaybackEngines"), editor_path.join("PlaybackEngines")];
+    roots
+        .iter()
+        .filter(|root| root.exists())
+        .flat_map(|root| WalkDir::new(root).max_depth(3).into_iter().filter_map(Result::ok))
+        .filter(|entry| entry.file_name() == "ivy.xml")
+        .find_map(|entry| {
+            let contents = fs::read_to_string(entry.path()).ok()?;
+            regex.captures(&contents)?[1].parse().ok()
+        })
+}
+
+fn read_modules_version(editor_path: &Path) -> Option<UnityVersion> {
+    let contents = fs::read_to_string(editor_path.join("modules.json")).ok()?;
+    let modules: Vec<Value> = serde_json::from_str(&contents).ok()?;
+    modules.into_iter().find_map(|module| [&module["url"], &module["downloadUrl"]].into_iter().filter_map(Value::as_str).find_map(UnityVersion::find_in))
+}
+
+fn read_folder_name_version(editor_path: &Path) -> Option<UnityVersion> {
+    UnityVersion::find_in(&editor_path.file_name()?.to_string_lossy())
+}
+
+fn launch_editor_for_version(executable_path: &Path) -> Result<UnityVersion, Box<dyn Error>> {
+    let mut child = Command::new(executable_path)
+        .arg("-batchmode")
+        .arg("-nographics")
+        .arg("--version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // The output is drained while waiting, so a chatty editor can't block on a full pipe.
+    let mut stdout = child.stdout.take().ok_or("Couldn't read the editor output")?;
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let deadline = Instant::now() + LAUNCH_TIMEOUT;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Err(format!("Timed out while asking {} for its version", executable_path.display()).into());
+        }
+        thread::sleep(LAUNCH_POLL_INTERVAL);
+    }
+    let output = reader.join().map_err(|_| "Couldn't read the editor output")??;
+    let output = String::from_utf8(output)?;
+    output.trim().parse::<UnityVersion>().map_err(|_| format!("Couldn't detect the version of {}", executable_path.display()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_file(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn version(version: &str) -> Option<UnityVersion> {
+        Some(version.parse().unwrap())
+    }
+
+    #[test]
+    fn reads_info_plist_version() {
+        let editor = TempDir::new().unwrap();
+        let plist = "<dict>\n<key>CFBundleShortVersionString</key>\n<string>Unity version 2022.3.10f1 (ff3792e53c62)</string>\n</dict>";
+        write_file(&system_info::get_editor_data_path(editor.path()).join("Info.plist"), plist);
+        assert_eq!(read_info_plist_version(editor.path()), version("2022.3.10f1"));
+    }
+
+    #[test]
+    fn reads_ivy_version() {
+        let editor = TempDir::new().unwrap();
+        let ivy_path = system_info::get_editor_data_path(editor.path()).join("PlaybackEngines").join("LinuxStandaloneSupport").join("ivy.xml");
+        write_file(&ivy_path, r#"<ivy-module><info version="1.0" unityVersion="2021.3.33f1" /></ivy-module>"#);
+        assert_eq!(read_ivy_version(editor.path()), version("2021.3.33f1"));
+    }
+
+    #[test]
+    fn reads_modules_version() {
+        let editor = TempDir::new().unwrap();
+        write_file(&editor.path().join("modules.json"), r#"[{"id":"android","downloadUrl":"https://download.unity3d.com/download_unity/abc/UnitySetup-Android-Support-for-Editor-6000.0.23f1.exe"}]"#);
+        assert_eq!(read_modules_version(editor.path()), version("6000.0.23f1"));
+    }
+
+    #[test]
+    fn reads_folder_name_version() {
+        let root = TempDir::new().unwrap();
+        assert_eq!(read_folder_name_version(&root.path().join("Unity 2023.2.1f1")), version("2023.2.1f1"));
+        assert_eq!(read_folder_name_version(&root.path().join("Unity")), None);
+    }
+
+    #[test]
+    fn editor_files_win_over_the_folder_name() {
+        let root = TempDir::new().unwrap();
+        let editor_path = root.path().join("2022.3.1f1");
+        write_file(&editor_path.join("modules.json"), r#"[{"id":"ios","url":"https://download.unity3d.com/download_unity/abc/UnitySetup-iOS-Support-for-Editor-2022.3.20f1.tar.xz"}]"#);
+        assert_eq!(detect_editor_version(&editor_path, &editor_path.join("missing")).unwrap().to_string(), "2022.3.20f1");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn launches_the_editor_as_a_last_resort() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = TempDir::new().unwrap();
+        let executable_path = root.path().join("Unity");
+        // More output than a pipe buffer holds, followed by the version.
+        write_file(&executable_path, "#!/bin/sh\nhead -c 200000 /dev/zero | tr '\\0' ' '\necho 2020.3.48f1\n");
+        fs::set_permissions(&executable_path, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(detect_editor_version(&root.path().join("Unity"), &executable_path).unwrap().to_string(), "2020.3.48f1");
+    }
+}
@@ -1,9 +1,11 @@
+use crate::editors::detect;
+use crate::live_api::release_info::FileType;
 use crate::system_info;
+use crate::version::UnityVersion;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub enum SystemArch {
@@ -32,12 +34,25 @@ impl From<SystemArch> for String {
 
 #[derive(Serialize, Deserialize)]
 pub struct EditorInfo {
-    pub version: String,
+    pub version: UnityVersion,
     #[serde(skip)]
     pub path: PathBuf,
     #[serde(skip)]
     pub executable_path: PathBuf,
     pub arch: SystemArch,
+    #[serde(default, rename = "downloadUrl", skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(default, rename = "downloadType", skip_serializing_if = "Option::is_none")]
+    pub download_type: Option<FileType>,
+    #[serde(default, rename = "downloadIntegrity", skip_serializing_if = "Option::is_none")]
+    pub download_integrity: Option<String>,
+}
+
+impl EditorInfo {
+    /// Only the editors wrum installed record how they were downloaded.
+    pub fn is_installed_by_wrum(&self) -> bool {
+        self.download_type.is_some()
+    }
 }
 
 pub fn read_editor_info(path: PathBuf) -> Result<Option<EditorInfo>, Box<dyn Error>> {
@@ -45,31 +60,22 @@ pub fn read_editor_info(path: PathBuf) -> Result<Option<EditorInfo>, Box<dyn Err
     if !editor_executable_path.exists() {
         return Ok(None);
     }
-    let cached_info_path = PathBuf::from(path.clone()).join("wrum.json");
+    let cached_info_path = path.join("wrum.json");
     match cached_info_path.exists() {
-        true => {
-            let contents = fs::read_to_string(cached_info_path)?;
-            let mut info: EditorInfo = serde_json::from_str(&contents)?;
-            info.path = path;
-            info.executable_path = editor_executable_path;
-            Ok(Some(info))
-        }
+        true => read_recorded_editor_info(path),
         false => {
-            let editor_version_output = Command::new(editor_executable_path.clone())
-                .arg("-batchmode")
-                .arg("-nographics")
-                .arg("--version")
-                .output()?
-                .stdout;
-            let editor_version = String::from_utf8(editor_version_output)?.trim().to_string();
+            let editor_version = detect::detect_editor_version(&path, &editor_executable_path)?;
             let editor_item = EditorInfo {
                 version: editor_version,
                 arch: system_info::get_editor_executable_arch(editor_executable_path.clone())?,
                 path,
                 executable_path: editor_executable_path,
+                download_url: None,
+                download_type: None,
+                download_integrity: None,
             };
             let json = serde_json::to_string(&editor_item)?;
-            if let Err(_) = fs::write(cached_info_path, json) {
+            if fs::write(cached_info_path, json).is_err() {
                 return Err("Couldn't write wrum.json".into());
             }
             Ok(Some(editor_item))
@@ -77,10 +83,22 @@ pub fn read_editor_info(path: PathBuf) -> Result<Option<EditorInfo>, Box<dyn Err
     }
 }
 
+pub fn read_recorded_editor_info(path: PathBuf) -> Result<Option<EditorInfo>, Box<dyn Error>> {
+    let cached_info_path = path.join("wrum.json");
+    if !cached_info_path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&cached_info_path)?;
+    let mut info: EditorInfo = serde_json::from_str(&contents).map_err(|err| format!("Couldn't read {}: {}", cached_info_path.display(), err))?;
+    info.executable_path = system_info::get_editor_executable_path(&path);
+    info.path = path;
+    Ok(Some(info))
+}
+
 pub fn write_editor_info(path: PathBuf, info: EditorInfo) -> Result<(), Box<dyn Error>> {
-    let cached_info_path = PathBuf::from(path.clone()).join("wrum.json");
+    let cached_info_path = path.join("wrum.json");
     let json = serde_json::to_string(&info)?;
-    if let Err(_) = fs::write(cached_info_path, json) {
+    if fs::write(cached_info_path, json).is_err() {
         return Err("Couldn't write wrum.json".into());
     }
     Ok(())
@@ -1,45 +1,99 @@
+pub(crate) mod archive;
+pub mod deep_link;
+mod detect;
 pub mod info;
+pub mod registry;
 
-use crate::editors::info::{read_editor_info, write_editor_info, EditorInfo, SystemArch};
+use crate::editors::info::{read_editor_info, read_recorded_editor_info, write_editor_info, EditorInfo, SystemArch};
 use crate::install::get_install_path;
 use crate::live_api::release_info;
-use crate::{install, live_api, modules, system_info};
+use crate::live_api::release_info::FileType;
+use crate::modules::info::ModuleInfo;
+use crate::modules::manifest;
+use crate::modules::manifest::ModuleManifest;
+use crate::progress::ProgressEvent;
+use crate::version::selector::VersionSelector;
+use crate::version::UnityVersion;
+use crate::{hub, install, live_api, modules, progress, system_info};
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::string::String;
 
-pub fn install_editor(version: &str, architecture: Option<String>) -> Result<i32, Box<dyn Error>> {
+pub(crate) struct EditorDownload {
+    url: String,
+    integrity: Option<String>,
+    file_type: FileType,
+    architecture: SystemArch,
+    download_size: f64,
+    installed_size: f64,
+    modules: Vec<ModuleInfo>,
+}
+
+impl EditorDownload {
+    fn from_api(download: release_info::ReleaseDownload) -> Result<Self, Box<dyn Error>> {
+        Ok(EditorDownload {
+            modules: modules::info::convert_api_modules(&download)?,
+            url: download.url,
+            integrity: download.integrity,
+            file_type: download.type_,
+            architecture: SystemArch::from(download.architecture),
+            download_size: download.download_size.download_size.into(),
+            installed_size: download.installed_size.download_size.into(),
+        })
+    }
+
+    fn from_local_archive(archive_path: &Path, architecture: SystemArch) -> Result<Self, Box<dyn Error>> {
+        Ok(EditorDownload {
+            url: String::new(),
+            integrity: None,
+            file_type: archive::get_file_type(&archive_path.to_string_lossy()),
+            architecture,
+            download_size: 0.0,
+            installed_size: archive::get_local_archive_installed_size(archive_path)? as f64,
+            modules: Vec::new(),
+        })
+    }
+}
+
+pub fn install_editor(version: &UnityVersion, architecture: Option<String>, changeset: Option<&str>) -> Result<i32, Box<dyn Error>> {
+    install_editor_from(version, architecture, changeset, None)
+}
+
+pub fn install_editor_from_archive(version: &UnityVersion, architecture: Option<String>, archive_path: &Path) -> Result<i32, Box<dyn Error>> {
+    if !archive_path.is_file() {
+        return Err(format!("{} does not exist", archive_path.display()).into());
+    }
+    install_editor_from(version, architecture, None, Some(archive_path))
+}
+
+fn install_editor_from(version: &UnityVersion, architecture: Option<String>, changeset: Option<&str>, archive_path: Option<&Path>) -> Result<i32, Box<dyn Error>> {
     let preferable_arch = match architecture.clone() {
         None => system_info::get_preferable_editor_arch(),
         Some(arch_str) => SystemArch::from(arch_str),
     };
 
-    let installed = list_installed_version(&version)?;
+    let installed = list_installed_version(version)?;
     if get_installed_editor_info(&installed, preferable_arch.clone()).is_some() {
-        println!("{} already installed!", version);
+        progress::report(ProgressEvent::AlreadyInstalled { id: version.to_string() });
         return Ok(0);
     }
 
-    let info = live_api::get_version_info(&version, system_info::get_platform(), system_info::get_supported_editor_arch())?;
-    if info.is_none() {
-        return Err("Couldn't find release".into());
-    }
-    let info = info.unwrap();
-    let mut download: Option<release_info::ReleaseDownload> = None;
-    for release_download in info.downloads {
-        let release_info::ReleaseDownloads::UnityReleaseHubDownload(release_download) = release_download;
-        let download_arch = SystemArch::from(release_download.architecture.clone());
-        if download.is_none() || download_arch == preferable_arch {
-            download = Some(release_download);
-        }
-    }
-    if download.is_none() {
-        return Err("Couldn't find any download for release".into());
-    }
-
-    let download = download.unwrap();
-    let download_arch = SystemArch::from(download.architecture.clone());
+    // A local archive is installed as is, without asking the release API about it.
+    let download = match archive_path {
+        Some(archive_path) => EditorDownload::from_local_archive(archive_path, preferable_arch)?,
+        None => match (get_api_download(version, preferable_arch.clone()), changeset) {
+            (Ok(Some(download)), _) => download,
+            (Ok(None), Some(changeset)) => archive::get_archive_download(version, changeset, preferable_arch)?,
+            (Err(err), Some(changeset)) => {
+                progress::warn(format!("couldn't look up {} in the release API, installing it from the download archive: {}", version, err));
+                archive::get_archive_download(version, changeset, preferable_arch)?
+            }
+            (Ok(None), None) => return Err("Couldn't find release. Specify its changeset to install it from the download archive".into()),
+            (Err(err), None) => return Err(err),
+        },
+    };
+    let download_arch = download.architecture.clone();
 
     if let Some(arch_str) = architecture {
         if SystemArch::from(arch_str) != download_arch {
@@ -48,7 +102,7 @@ pub fn install_editor(version: &str, architecture: Option<String>) -> Result<i32
     }
 
     if get_installed_editor_info(&installed, download_arch.clone()).is_some() {
-        println!("{} already installed!", version);
+        progress::report(ProgressEvent::AlreadyInstalled { id: version.to_string() });
         return Ok(0);
     }
 
@@ -58,35 +112,38 @@ pub fn install_editor(version: &str, architecture: Option<String>) -> Result<i32
     }
     let mut editor_path = get_install_path()?;
 
-    let mut required_disk_space: u64 = 0;
-    let installed_size: f64 = download.installed_size.clone().download_size.into();
-    let download_size: f64 = download.download_size.clone().download_size.into();
-    required_disk_space += (installed_size + download_size) as u64;
-
+    let required_disk_space = match archive_path {
+        None => (download.installed_size + download.download_size) as u64,
+        Some(_) => download.installed_size as u64,
+    };
     if fs4::available_space(editor_path.clone()).unwrap() < required_disk_space {
         return Err("Not enough free disk space".into());
     }
     editor_path.push(version_path);
 
-    let lock_file = install::get_install_lock(&version, &editor_path)?;
-    let installed = list_installed_version(&version)?;
+    let lock_file = install::get_install_lock(&version.to_string(), &editor_path)?;
+    let installed = list_installed_version(version)?;
     if get_installed_editor_info(&installed, download_arch.clone()).is_none() {
-        install::install(&download.url, &version, &editor_path, download.type_.clone(), "{UNITY_PATH}",
-                         system_info::get_editor_install_move_path(&editor_path).unwrap_or(PathBuf::new()).to_str().unwrap(),
-                         &editor_path.to_str().unwrap())?;
+        let files = install_editor_files(&download.url, download.integrity.as_deref(), archive_path, version, &editor_path, download.file_type.clone())?;
+        manifest::write_manifest(&editor_path, &ModuleManifest::new(&version.to_string(), &editor_path, files)?)?;
 
-        let modules = modules::info::convert_api_modules(&download);
-        modules::write_modules_info(&editor_path, modules.unwrap())?;
+        modules::write_modules_info(&editor_path, download.modules)?;
 
         write_editor_info(
-            editor_path,
+            editor_path.clone(),
             EditorInfo {
-                version: version.to_string(),
+                version: version.clone(),
                 path: PathBuf::new(),
                 executable_path: PathBuf::new(),
-                arch: SystemArch::from(download.architecture),
+                arch: download_arch,
+                download_url: Some(download.url).filter(|url| !url.is_empty()),
+                download_type: Some(download.file_type),
+                download_integrity: download.integrity,
             },
         )?;
+        if let Some(editor_info) = read_editor_info(editor_path)? {
+            add_hub_editor(&editor_info);
+        }
     }
 
     install::release_install_lock(lock_file)?;
@@ -94,11 +151,71 @@ pub fn install_editor(version: &str, architecture: Option<String>) -> Result<i32
     Ok(0)
 }
 
-pub fn list_installed_version(version: &str) -> Result<Vec<EditorInfo>, Box<dyn Error>> {
+pub(crate) fn install_editor_files(url: &str, integrity: Option<&str>, archive_path: Option<&Path>, version: &UnityVersion, editor_path: &Path,
+                                   file_type: FileType) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let move_path = system_info::get_editor_install_move_path(editor_path).unwrap_or_default();
+    match archive_path {
+        None => install::install(url, integrity, &version.to_string(), editor_path, file_type, "{UNITY_PATH}", move_path.to_str().unwrap(),
+                                 editor_path.to_str().unwrap()),
+        Some(archive_path) => install::install_file(archive_path, &version.to_string(), editor_path, file_type, "{UNITY_PATH}",
+                                                    move_path.to_str().unwrap(), editor_path.to_str().unwrap()),
+    }
+}
+
+pub fn uninstall_editor(version: &UnityVersion, architecture: Option<String>) -> Result<i32, Box<dyn Error>> {
+    let installed = list_installed_version(version)?;
+    let editor_info = match architecture {
+        Some(arch_str) => get_installed_editor_info(&installed, SystemArch::from(arch_str)),
+        None => match installed.len() {
+            0 | 1 => installed.first(),
+            _ => return Err(format!("Several architectures of {} are installed. Specify the one to uninstall", version).into()),
+        },
+    };
+    let editor_info = editor_info.ok_or(format!("{} is not installed", version))?;
+    // Editors added by path or found through Unity Hub can be shared or provisioned, they are never deleted.
+    if !editor_info.is_installed_by_wrum() {
+        return Err(format!("{} in {} wasn't installed by wrum. Remove it with the tool that installed it", version, editor_info.path.display()).into());
+    }
+
+    let lock_files = install::try_get_install_locks(&editor_info.path)?.ok_or(format!("{} is being installed by another process", version))?;
+    if system_info::is_executable_running(&editor_info.executable_path)? {
+        return Err(format!("{} is running. Close it before uninstalling", version).into());
+    }
+
+    // The lock files are removed last and only released once they are gone, so no install can start halfway through.
+    progress::report(ProgressEvent::UninstallStarted { id: version.to_string() });
+    let mut lock_paths = Vec::new();
+    for child in fs::read_dir(&editor_info.path)? {
+        let entry = child?.path();
+        if install::is_install_lock_path(&entry) {
+            lock_paths.push(entry);
+            continue;
+        }
+        match entry.is_dir() && !entry.is_symlink() {
+            true => fs::remove_dir_all(entry)?,
+            false => fs::remove_file(entry)?,
+        }
+    }
+    for lock_path in lock_paths {
+        fs::remove_file(lock_path)?;
+    }
+    for lock_file in lock_files {
+        install::release_install_lock(lock_file)?;
+    }
+    fs::remove_dir(&editor_info.path)?;
+    registry::unregister_editor_path(&editor_info.path)?;
+    if let Err(err) = hub::remove_hub_editor(&editor_info.path) {
+        progress::warn(format!("failed to update the Unity Hub editor list: {}", err));
+    }
+    progress::report(ProgressEvent::UninstallFinished { id: version.to_string() });
+    Ok(0)
+}
+
+pub fn list_installed_version(version: &UnityVersion) -> Result<Vec<EditorInfo>, Box<dyn Error>> {
     let mut installed = Vec::new();
     let editors = list_installed_editors()?;
     for editor in editors {
-        if editor.version != version {
+        if &editor.version != version {
             continue;
         }
         installed.push(editor);
@@ -106,23 +223,40 @@ pub fn list_installed_version(version: &str) -> Result<Vec<EditorInfo>, Box<dyn
     Ok(installed)
 }
 
+pub fn resolve_installed_version(selector: &VersionSelector) -> Result<UnityVersion, Box<dyn Error>> {
+    if let VersionSelector::Exact(version) = selector {
+        return Ok(version.clone());
+    }
+    let installed = list_installed_editors()?.into_iter().map(|editor| editor.version);
+    selector.select(installed).ok_or(format!("Couldn't find any installed editor matching \"{}\"", selector).into())
+}
+
 pub fn list_installed_editors() -> Result<Vec<EditorInfo>, Box<dyn Error>> {
     let mut editors = Vec::new();
     list_editors_in_folder(system_info::get_default_install_path(), &mut editors)?;
     append_secondary_path_editors(&mut editors)?;
+    append_registered_editors(&mut editors)?;
+    append_hub_editors(&mut editors)?;
+    editors.sort_by(|a, b| a.version.cmp(&b.version));
     Ok(editors)
 }
 
-pub fn get_installed_editor_info(installed: & Vec<EditorInfo>, arch: SystemArch) -> Option<&EditorInfo> {
-    for editor in installed {
-        if editor.arch == arch {
-            return Some(editor);
-        }
+pub fn add_editor(path: impl AsRef<Path>) -> Result<EditorInfo, Box<dyn Error>> {
+    let path = fs::canonicalize(path.as_ref()).map_err(|_| format!("{} does not exist", path.as_ref().display()))?;
+    if !system_info::get_editor_executable_path(&path).exists() {
+        return Err(format!("Couldn't find an editor executable in {}", path.display()).into());
     }
-    None
+    let editor_info = read_editor_info(path.clone())?.ok_or(format!("Couldn't detect the editor version in {}", path.display()))?;
+    registry::register_editor_path(&path)?;
+    add_hub_editor(&editor_info);
+    Ok(editor_info)
 }
 
-pub fn get_installed_editor_path(version: &str, arch: Option<String>) -> Result<Option<PathBuf>, Box<dyn Error>> {
+pub fn get_installed_editor_info(installed: &[EditorInfo], arch: SystemArch) -> Option<&EditorInfo> {
+    installed.iter().find(|editor| editor.arch == arch)
+}
+
+pub fn get_installed_editor_path(version: &UnityVersion, arch: Option<String>) -> Result<Option<PathBuf>, Box<dyn Error>> {
     let installed_version = list_installed_version(version)?;
     match arch {
         Some(arch_str) => {
@@ -144,6 +278,61 @@ pub fn get_installed_editor_path(version: &str, arch: Option<String>) -> Result<
     }
 }
 
+/// Finds an editor from its `wrum.json` alone, so editors whose executable was deleted are found too.
+pub fn find_recorded_editor(version: &UnityVersion, arch: Option<String>) -> Result<Option<EditorInfo>, Box<dyn Error>> {
+    let mut folders = Vec::new();
+    for install_path in [Some(system_info::get_default_install_path()), install::get_secondary_install_path()?].into_iter().flatten() {
+        if !install_path.exists() {
+            continue;
+        }
+        for child in fs::read_dir(install_path)? {
+            folders.push(child?.path());
+        }
+    }
+    folders.extend(registry::get_registered_editor_paths()?);
+    folders.extend(hub::get_hub_editor_paths()?);
+
+    let arch = arch.map(SystemArch::from);
+    let mut found: Option<EditorInfo> = None;
+    for folder in folders {
+        let editor = match read_recorded_editor_info(folder.clone()) {
+            Ok(Some(editor)) if &editor.version == version => editor,
+            Ok(_) => continue,
+            Err(err) => {
+                progress::warn(format!("skipping the editor in {}: {}", folder.display(), err));
+                continue;
+            }
+        };
+        match &arch {
+            Some(arch) if &editor.arch == arch => return Ok(Some(editor)),
+            Some(_) => continue,
+            None if found.is_none() || editor.arch == system_info::get_preferable_editor_arch() => found = Some(editor),
+            None => continue,
+        }
+    }
+    Ok(found)
+}
+
+fn get_api_download(version: &UnityVersion, preferable_arch: SystemArch) -> Result<Option<EditorDownload>, Box<dyn Error>> {
+    let info = live_api::get_version_info(version, system_info::get_platform(), system_info::get_supported_editor_arch())?;
+    let info = match info {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let mut download: Option<release_info::ReleaseDownload> = None;
+    for release_download in info.downloads {
+        let release_info::ReleaseDownloads::UnityReleaseHubDownload(release_download) = release_download;
+        let download_arch = SystemArch::from(release_download.architecture.clone());
+        if download.is_none() || download_arch == preferable_arch {
+            download = Some(release_download);
+        }
+    }
+    match download {
+        None => Err("Couldn't find any download for release".into()),
+        Some(download) => Ok(Some(EditorDownload::from_api(download)?)),
+    }
+}
+
 fn append_secondary_path_editors(editors: &mut Vec<EditorInfo>) -> Result<(), Box<dyn Error>> {
     let secondary_path = install::get_secondary_install_path()?;
     if let Some(secondary_path) = secondary_path {
@@ -152,6 +341,47 @@ fn append_secondary_path_editors(editors: &mut Vec<EditorInfo>) -> Result<(), Bo
     Ok(())
 }
 
+fn append_registered_editors(editors: &mut Vec<EditorInfo>) -> Result<(), Box<dyn Error>> {
+    for path in registry::get_registered_editor_paths()? {
+        if editors.iter().any(|editor| editor.path == path) {
+            continue;
+        }
+        if let Some(item) = read_listed_editor_info(path) {
+            editors.push(item);
+        }
+    }
+    Ok(())
+}
+
+fn append_hub_editors(editors: &mut Vec<EditorInfo>) -> Result<(), Box<dyn Error>> {
+    for path in hub::get_hub_editor_paths()? {
+        if editors.iter().any(|editor| editor.path == path) {
+            continue;
+        }
+        if let Some(item) = read_listed_editor_info(path) {
+            editors.push(item);
+        }
+    }
+    Ok(())
+}
+
+fn add_hub_editor(editor_info: &EditorInfo) {
+    if let Err(err) = hub::add_hub_editor(editor_info) {
+        progress::warn(format!("failed to update the Unity Hub editor list: {}", err));
+    }
+}
+
+// A single unreadable editor shouldn't hide the other ones.
+fn read_listed_editor_info(path: PathBuf) -> Option<EditorInfo> {
+    match read_editor_info(path.clone()) {
+        Ok(editor_info) => editor_info,
+        Err(err) => {
+            progress::warn(format!("skipping the editor in {}: {}", path.display(), err));
+            None
+        }
+    }
+}
+
 fn list_editors_in_folder(path: PathBuf, editors: &mut Vec<EditorInfo>) -> Result<(), Box<dyn Error>> {
     if !path.exists() {
         return Ok(());
@@ -161,9 +391,107 @@ fn list_editors_in_folder(path: PathBuf, editors: &mut Vec<EditorInfo>) -> Resul
         if !entry.is_dir() {
             continue;
         }
-        if let Some(item) = read_editor_info(entry)? {
+        if let Some(item) = read_listed_editor_info(entry) {
             editors.push(item);
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn create_editor(path: &Path, wrum_json: &str) {
+        let executable_path = system_info::get_editor_executable_path(path);
+        fs::create_dir_all(executable_path.parent().unwrap()).unwrap();
+        fs::write(executable_path, "").unwrap();
+        fs::write(path.join("wrum.json"), wrum_json).unwrap();
+    }
+
+    #[test]
+    fn unreadable_editors_are_skipped() {
+        let install_path = TempDir::new().unwrap();
+        create_editor(&install_path.path().join("2022.3.10f1"), r#"{"version":"2022.3.10f1","arch":"X86_64"}"#);
+        create_editor(&install_path.path().join("broken"), r#"{"version":"2022.3","arch":"X86_64"}"#);
+        create_editor(&install_path.path().join("truncated"), r#"{"version":"#);
+
+        let mut editors = Vec::new();
+        list_editors_in_folder(install_path.path().to_path_buf(), &mut editors).unwrap();
+        assert_eq!(editors.len(), 1);
+        assert_eq!(editors[0].version.to_string(), "2022.3.10f1");
+    }
+
+    #[test]
+    fn recorded_editors_skip_unreadable_folders() {
+        let _config = crate::test_support::set_test_config(crate::config::Config::default());
+        let version = UnityVersion::from_str("2021.3.34f1").unwrap();
+        let editor_path = system_info::get_default_install_path().join(version.to_string());
+        let broken_path = system_info::get_default_install_path().join("broken-2021.3.34f1");
+        create_editor(&editor_path, r#"{"version":"2021.3.34f1","arch":"X86_64"}"#);
+        create_editor(&broken_path, r#"{"version":"#);
+
+        let editor = find_recorded_editor(&version, None).unwrap().unwrap();
+        assert_eq!(editor.path, editor_path);
+        fs::remove_dir_all(editor_path).unwrap();
+        fs::remove_dir_all(broken_path).unwrap();
+    }
+
+    #[test]
+    fn uninstall_removes_lock_files_last_and_respects_held_locks() {
+        let _config = crate::test_support::set_test_config(crate::config::Config::default());
+        let version = UnityVersion::from_str("2021.3.33f1").unwrap();
+        let editor_path = system_info::get_default_install_path().join(version.to_string());
+        create_editor(&editor_path, r#"{"version":"2021.3.33f1","arch":"X86_64","downloadType":"TAR_XZ"}"#);
+
+        let held_lock = install::get_install_lock("Editor", &editor_path).unwrap();
+        let err = uninstall_editor(&version, None).unwrap_err();
+        assert!(err.to_string().contains("is being installed by another process"), "{}", err);
+        assert!(editor_path.join("wrum.json").exists());
+        install::release_install_lock(held_lock).unwrap();
+
+        uninstall_editor(&version, None).unwrap();
+        assert!(!editor_path.exists());
+    }
+
+    #[test]
+    fn uninstall_refuses_editors_wrum_did_not_install() {
+        let _config = crate::test_support::set_test_config(crate::config::Config::default());
+        let version = UnityVersion::from_str("2021.3.35f1").unwrap();
+        let editor_path = system_info::get_default_install_path().join(version.to_string());
+        create_editor(&editor_path, r#"{"version":"2021.3.35f1","arch":"X86_64"}"#);
+
+        let err = uninstall_editor(&version, None).unwrap_err();
+        assert!(err.to_string().contains("wasn't installed by wrum"), "{}", err);
+        assert!(system_info::get_editor_executable_path(&editor_path).exists());
+        fs::remove_dir_all(editor_path).unwrap();
+    }
+
+    #[test]
+    fn installing_from_an_archive_skips_the_release_api() {
+        let server = crate::test_support::TestServer::start(|_| crate::test_support::TestResponse::new(500, "unexpected"));
+        let _config = crate::test_support::set_test_config(crate::config::Config { api_url: server.url("/graphql"), ..crate::config::Config::default() });
+        let archive_folder = TempDir::new().unwrap();
+        let archive_path = archive_folder.path().join("Unity-2020.1.2f1.tar.gz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(fs::File::create(&archive_path).unwrap(), flate2::Compression::default()));
+        let mut folder_header = tar::Header::new_gnu();
+        folder_header.set_entry_type(tar::EntryType::Directory);
+        folder_header.set_size(0);
+        folder_header.set_mode(0o755);
+        builder.append_data(&mut folder_header, "Editor/", std::io::empty()).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.set_mode(0o755);
+        builder.append_data(&mut header, "Editor/Unity", std::io::empty()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        let version = UnityVersion::from_str("2020.1.2f1").unwrap();
+
+        install_editor_from_archive(&version, Some(String::from("x86_64")), &archive_path).unwrap();
+        assert!(server.take_requests().is_empty());
+        let editor_path = get_installed_editor_path(&version, None).unwrap().unwrap();
+        assert!(system_info::get_editor_executable_path(&editor_path).exists());
+        uninstall_editor(&version, None).unwrap();
+    }
+}
@@ -0,0 +1,43 @@
+use crate::system_info;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static REGISTRY_FILE_NAME: &str = "editors.json";
+
+pub fn get_registered_editor_paths() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let registry_path = system_info::get_wrum_config_path().join(REGISTRY_FILE_NAME);
+    if !registry_path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(registry_path)?;
+    serde_json::from_str(&contents).map_err(|_| "Couldn't read editors.json.".into())
+}
+
+pub fn register_editor_path(path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let mut paths = get_registered_editor_paths()?;
+    if paths.iter().any(|registered_path| registered_path == path.as_ref()) {
+        return Ok(());
+    }
+    paths.push(PathBuf::from(path.as_ref()));
+    write_registered_editor_paths(&paths)
+}
+
+pub fn unregister_editor_path(path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let mut paths = get_registered_editor_paths()?;
+    let count = paths.len();
+    paths.retain(|registered_path| registered_path != path.as_ref());
+    if paths.len() == count {
+        return Ok(());
+    }
+    write_registered_editor_paths(&paths)
+}
+
+fn write_registered_editor_paths(paths: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+    let registry_path = system_info::get_wrum_config_path().join(REGISTRY_FILE_NAME);
+    let json = serde_json::to_string_pretty(paths)?;
+    match fs::write(registry_path, json) {
+        Ok(_) => Ok(()),
+        Err(_) => Err("Couldn't write editors.json.".into()),
+    }
+}
@@ -0,0 +1,175 @@
+use crate::editors::info::EditorInfo;
+use crate::{progress, system_info};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+static EDITORS_FILE_NAME: &str = "editors-v2.json";
+static PROJECTS_FILE_NAME: &str = "projects-v1.json";
+
+pub fn get_hub_editor_paths() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let editors = match read_hub_file(EDITORS_FILE_NAME) {
+        None => return Ok(Vec::new()),
+        Some(editors) => editors,
+    };
+    let mut paths = Vec::new();
+    for editor in editors["data"].as_array().into_iter().flatten() {
+        let location = editor["location"].as_array().and_then(|locations| locations.first()).and_then(Value::as_str);
+        if let Some(path) = location.and_then(system_info::get_editor_path_from_hub_location) {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+pub fn add_hub_editor(editor: &EditorInfo) -> Result<(), Box<dyn Error>> {
+    let mut editors = match read_hub_file(EDITORS_FILE_NAME) {
+        None => return Ok(()),
+        Some(editors) => editors,
+    };
+    let location = system_info::get_hub_editor_location(&editor.path);
+    let entries = get_array_mut(&mut editors, "data")?;
+    entries.retain(|entry| !has_location(entry, &location));
+    entries.push(json!({
+        "version": editor.version.to_string(),
+        "location": [location],
+        "manual": true,
+        "architecture": String::from(editor.arch.clone()),
+        "productName": format!("Unity {}", editor.version),
+    }));
+    write_hub_file(EDITORS_FILE_NAME, &editors)
+}
+
+pub fn remove_hub_editor(editor_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let mut editors = match read_hub_file(EDITORS_FILE_NAME) {
+        None => return Ok(()),
+        Some(editors) => editors,
+    };
+    let location = system_info::get_hub_editor_location(editor_path);
+    let entries = get_array_mut(&mut editors, "data")?;
+    let count = entries.len();
+    entries.retain(|entry| !has_location(entry, &location));
+    if entries.len() == count {
+        return Ok(());
+    }
+    write_hub_file(EDITORS_FILE_NAME, &editors)
+}
+
+pub fn add_hub_project(project_path: impl AsRef<Path>, editor: &EditorInfo) -> Result<(), Box<dyn Error>> {
+    let mut projects = match read_hub_file(PROJECTS_FILE_NAME) {
+        None => return Ok(()),
+        Some(projects) => projects,
+    };
+    let project_path = fs::canonicalize(project_path)?;
+    let path_string = project_path.to_string_lossy().to_string();
+    let last_modified = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_millis() as u64;
+    if !projects["data"].is_object() {
+        projects["data"] = Value::Object(Map::new());
+    }
+    let entries = projects["data"].as_object_mut().ok_or("Couldn't read projects-v1.json.")?;
+    let entry = entries.entry(path_string.clone()).or_insert(json!({ "isFavorite": false }));
+    entry["title"] = json!(project_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+    entry["path"] = json!(path_string);
+    entry["containingFolderPath"] = json!(project_path.parent().map(|parent| parent.to_string_lossy().to_string()).unwrap_or_default());
+    entry["version"] = json!(editor.version.to_string());
+    entry["architecture"] = json!(String::from(editor.arch.clone()));
+    entry["lastModified"] = json!(last_modified);
+    write_hub_file(PROJECTS_FILE_NAME, &projects)
+}
+
+fn has_location(entry: &Value, location: &Path) -> bool {
+    entry["location"].as_array().into_iter().flatten().filter_map(Value::as_str).any(|entry_location| Path::new(entry_location) == location)
+}
+
+fn get_array_mut<'a>(value: &'a mut Value, key: &str) -> Result<&'a mut Vec<Value>, Box<dyn Error>> {
+    if !value[key].is_array() {
+        value[key] = Value::Array(Vec::new());
+    }
+    value[key].as_array_mut().ok_or(format!("Couldn't read the Unity Hub {} list.", key).into())
+}
+
+/// An unreadable Hub file is left untouched and treated as missing, so it never gets in the way of wrum.
+fn read_hub_file(file_name: &str) -> Option<Value> {
+    let file_path = system_info::get_config_path().join(file_name);
+    if !file_path.exists() {
+        return None;
+    }
+    let value = fs::read_to_string(&file_path).map_err(|err| err.to_string()).and_then(|contents| serde_json::from_str(&contents).map_err(|err| err.to_string()));
+    match value {
+        Ok(Value::Object(value)) => Some(Value::Object(value)),
+        Ok(_) => {
+            progress::warn(format!("ignoring the Unity Hub file {}, it doesn't hold a JSON object", file_path.display()));
+            None
+        }
+        Err(err) => {
+            progress::warn(format!("ignoring the Unity Hub file {}: {}", file_path.display(), err));
+            None
+        }
+    }
+}
+
+/// Writes a temporary file next to the Hub file and renames it, so Unity Hub never reads a partial file.
+fn write_hub_file(file_name: &str, value: &Value) -> Result<(), Box<dyn Error>> {
+    let file_path = system_info::get_config_path().join(file_name);
+    let temp_file_path = file_path.with_extension("json.tmp");
+    match fs::write(&temp_file_path, serde_json::to_string(value)?).and_then(|_| fs::rename(&temp_file_path, &file_path)) {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let _ = fs::remove_file(temp_file_path);
+            Err(format!("Couldn't write {}.", file_name).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::editors::info::SystemArch;
+    use crate::test_support;
+
+    #[test]
+    fn malformed_hub_files_are_ignored() {
+        let _config = test_support::set_test_config(Config::default());
+        let editors_path = system_info::get_config_path().join(EDITORS_FILE_NAME);
+        fs::create_dir_all(editors_path.parent().unwrap()).unwrap();
+        fs::write(&editors_path, "{\"data\": [").unwrap();
+
+        assert!(get_hub_editor_paths().unwrap().is_empty());
+        remove_hub_editor("/nowhere").unwrap();
+        assert_eq!(fs::read_to_string(&editors_path).unwrap(), "{\"data\": [");
+        fs::remove_file(editors_path).unwrap();
+    }
+
+    #[test]
+    fn unknown_fields_survive_a_write() {
+        let _config = test_support::set_test_config(Config::default());
+        let editors_path = system_info::get_config_path().join(EDITORS_FILE_NAME);
+        fs::create_dir_all(editors_path.parent().unwrap()).unwrap();
+        let other_editor = json!({ "version": "2021.3.1f1", "location": ["/other/Unity"], "manual": false, "hubSpecific": { "keep": true } });
+        fs::write(&editors_path, json!({ "schema_version": "v2", "data": [other_editor.clone()] }).to_string()).unwrap();
+        let editor = EditorInfo {
+            version: "2022.3.10f1".parse().unwrap(),
+            path: PathBuf::from("/editors/2022.3.10f1"),
+            executable_path: PathBuf::new(),
+            arch: SystemArch::X86_64,
+            download_url: None,
+            download_type: None,
+            download_integrity: None,
+        };
+
+        add_hub_editor(&editor).unwrap();
+        let editors: Value = serde_json::from_str(&fs::read_to_string(&editors_path).unwrap()).unwrap();
+        assert_eq!(editors["schema_version"], "v2");
+        assert_eq!(editors["data"][0], other_editor);
+        assert_eq!(editors["data"][1]["version"], "2022.3.10f1");
+        assert!(!editors_path.with_extension("json.tmp").exists());
+
+        remove_hub_editor(&editor.path).unwrap();
+        let editors: Value = serde_json::from_str(&fs::read_to_string(&editors_path).unwrap()).unwrap();
+        assert_eq!(editors, json!({ "schema_version": "v2", "data": [other_editor] }));
+        fs::remove_file(editors_path).unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+use crate::disk_usage::get_folder_size;
+use crate::progress::ProgressEvent;
+use crate::{config, progress};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static ENTRIES_FOLDER_NAME: &str = "entries";
+static LOCKS_FOLDER_NAME: &str = "locks";
+static ENTRY_FILE_NAME: &str = "entry.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub key: String,
+    pub url: String,
+    pub integrity: Option<String>,
+    pub file_name: String,
+    pub size: u64,
+    pub last_used: u64,
+}
+
+/// An exclusively locked cache slot. The lock is released when the slot is dropped.
+pub(crate) struct CacheSlot {
+    key: String,
+    url: String,
+    integrity: Option<String>,
+    path: PathBuf,
+    lock_file: File,
+}
+
+impl CacheSlot {
+    /// Returns the cached file, or None when the slot is empty or its file changed. Files are verified before
+    /// they are stored, so hits only compare the size instead of hashing the file again.
+    pub(crate) fn get_file(&self) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        let mut entry = match read_entry(&self.path)? {
+            None => return Ok(None),
+            Some(entry) => entry,
+        };
+        let file_path = self.path.join(&entry.file_name);
+        if !fs::metadata(&file_path).is_ok_and(|metadata| metadata.is_file() && metadata.len() == entry.size) {
+            fs::remove_dir_all(&self.path)?;
+            return Ok(None);
+        }
+        entry.last_used = now();
+        write_entry(&self.path, &entry)?;
+        Ok(Some(file_path))
+    }
+
+    /// Moves a downloaded file into the slot and returns its new path.
+    pub(crate) fn store_file(&self, file_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        if self.path.exists() {
+            fs::remove_dir_all(&self.path)?;
+        }
+        fs::create_dir_all(&self.path)?;
+        let file_name = file_path.file_name().ok_or("Invalid download path")?.to_string_lossy().to_string();
+        let cached_file_path = self.path.join(&file_name);
+        if fs::rename(file_path, &cached_file_path).is_err() {
+            fs::copy(file_path, &cached_file_path)?;
+            fs::remove_file(file_path)?;
+        }
+        let entry = CacheEntry {
+            key: self.key.clone(),
+            url: self.url.clone(),
+            integrity: self.integrity.clone(),
+            file_name,
+            size: fs::metadata(&cached_file_path)?.len(),
+            last_used: now(),
+        };
+        write_entry(&self.path, &entry)?;
+        Ok(cached_file_path)
+    }
+}
+
+impl Drop for CacheSlot {
+    fn drop(&mut self) {
+        let _ = self.lock_file.unlock();
+    }
+}
+
+pub fn get_cache_path() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    Ok(config::get_config()?.download_cache_path)
+}
+
+/// Locks the cache slot of a download, or returns None when the download cache is disabled.
+pub(crate) fn lock_slot(url: &str, integrity: Option<&str>) -> Result<Option<CacheSlot>, Box<dyn Error>> {
+    let cache_path = match get_cache_path()? {
+        None => return Ok(None),
+        Some(cache_path) => cache_path,
+    };
+    let integrity = integrity.filter(|integrity| !integrity.is_empty() && !config::get_config().is_ok_and(|config| config.skip_integrity));
+    // Verified downloads are addressed by their content so mirrors share entries, the others by their url.
+    let key = match integrity {
+        Some(integrity) => get_key(&format!("integrity:{}", integrity)),
+        None => get_key(&format!("url:{}", url)),
+    };
+    let lock_file = loop {
+        let lock_file = open_lock_file(&cache_path, &key)?;
+        if lock_file.try_lock_exclusive().is_err() {
+            progress::report(ProgressEvent::WaitingForLock { id: String::from(url) });
+            lock_file.lock_exclusive()?;
+        }
+        // Pruning deletes lock files while holding them, a lock on a deleted file doesn't exclude anyone.
+        if get_lock_path(&cache_path, &key).exists() {
+            break lock_file;
+        }
+        lock_file.unlock()?;
+    };
+    Ok(Some(CacheSlot {
+        path: cache_path.join(ENTRIES_FOLDER_NAME).join(&key),
+        key,
+        url: String::from(url),
+        integrity: integrity.map(String::from),
+        lock_file,
+    }))
+}
+
+pub fn list_entries() -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+    read_entries(&get_enabled_cache_path()?, &mut Vec::new())
+}
+
+/// Removes the least recently used entries until the cache fits in max_size bytes. Entries in use are kept.
+/// Folders left without a readable entry and locks left without a folder are removed whatever the size.
+pub fn prune(max_size: u64) -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+    let cache_path = get_enabled_cache_path()?;
+    let mut orphans = Vec::new();
+    let mut entries = read_entries(&cache_path, &mut orphans)?;
+    let mut removed = Vec::new();
+    for orphan in orphans {
+        if remove_entry(&cache_path, &orphan.key)? {
+            removed.push(orphan);
+        }
+    }
+    let mut total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+    while total_size > max_size {
+        let entry = match entries.pop() {
+            None => break,
+            Some(entry) => entry,
+        };
+        if remove_entry(&cache_path, &entry.key)? {
+            total_size -= entry.size;
+            removed.push(entry);
+        }
+    }
+    remove_stale_locks(&cache_path)?;
+    Ok(removed)
+}
+
+/// Removes the least recently used entries when the cache grew over the configured size.
+pub(crate) fn prune_to_configured_size() -> Result<(), Box<dyn Error>> {
+    let max_size = config::get_config()?.download_cache_max_size;
+    if max_size > 0 {
+        prune(max_size)?;
+    }
+    Ok(())
+}
+
+pub fn clear() -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+    prune(0)
+}
+
+/// Reads the entries, most recently used first. Folders without a readable entry are added to orphans.
+fn read_entries(cache_path: &Path, orphans: &mut Vec<CacheEntry>) -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+    let entries_path = cache_path.join(ENTRIES_FOLDER_NAME);
+    let mut entries = Vec::new();
+    if !entries_path.exists() {
+        return Ok(entries);
+    }
+    for child in fs::read_dir(entries_path)? {
+        let child_path = child?.path();
+        if !child_path.is_dir() {
+            continue;
+        }
+        match read_entry(&child_path) {
+            Ok(Some(entry)) => entries.push(entry),
+            result => {
+                if let Err(err) = result {
+                    progress::warn(format!("unreadable download cache entry {}: {}", child_path.display(), err));
+                }
+                orphans.push(CacheEntry {
+                    key: child_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    url: String::new(),
+                    integrity: None,
+                    file_name: String::new(),
+                    size: get_folder_size(&child_path),
+                    last_used: 0,
+                });
+            }
+        }
+    }
+    entries.sort_by_key(|entry| Reverse(entry.last_used));
+    Ok(entries)
+}
+
+fn remove_entry(cache_path: &Path, key: &str) -> Result<bool, Box<dyn Error>> {
+    let lock_file = open_lock_file(cache_path, key)?;
+    if lock_file.try_lock_exclusive().is_err() {
+        return Ok(false);
+    }
+    let entry_path = cache_path.join(ENTRIES_FOLDER_NAME).join(key);
+    if entry_path.exists() {
+        fs::remove_dir_all(entry_path)?;
+    }
+    fs::remove_file(get_lock_path(cache_path, key))?;
+    lock_file.unlock()?;
+    Ok(true)
+}
+
+fn remove_stale_locks(cache_path: &Path) -> Result<(), Box<dyn Error>> {
+    let locks_path = cache_path.join(LOCKS_FOLDER_NAME);
+    if !locks_path.exists() {
+        return Ok(());
+    }
+    for child in fs::read_dir(locks_path)? {
+        let child_path = child?.path();
+        let key = match child_path.file_name().and_then(|name| name.to_str()).and_then(|name| name.strip_suffix(".lock")) {
+            None => continue,
+            Some(key) => key,
+        };
+        if cache_path.join(ENTRIES_FOLDER_NAME).join(key).exists() {
+            continue;
+        }
+        let lock_file = File::options().write(true).open(&child_path)?;
+        if lock_file.try_lock_exclusive().is_ok() {
+            fs::remove_file(&child_path)?;
+            lock_file.unlock()?;
+        }
+    }
+    Ok(())
+}
+
+fn get_enabled_cache_path() -> Result<PathBuf, Box<dyn Error>> {
+    get_cache_path()?.ok_or("The download cache is disabled. Set downloadCachePath in the config or WRUM_DOWNLOAD_CACHE_PATH to enable it".into())
+}
+
+fn open_lock_file(cache_path: &Path, key: &str) -> Result<File, Box<dyn Error>> {
+    fs::create_dir_all(cache_path.join(LOCKS_FOLDER_NAME))?;
+    Ok(File::options().write(true).create(true).truncate(false).open(get_lock_path(cache_path, key))?)
+}
+
+fn get_lock_path(cache_path: &Path, key: &str) -> PathBuf {
+    cache_path.join(LOCKS_FOLDER_NAME).join(format!("{}.lock", key))
+}
+
+fn read_entry(entry_path: &Path) -> Result<Option<CacheEntry>, Box<dyn Error>> {
+    let entry_file_path = entry_path.join(ENTRY_FILE_NAME);
+    if !entry_file_path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(entry_file_path)?)?))
+}
+
+/// Writes the entry to a temporary file first so an interrupted write never leaves a truncated entry.
+fn write_entry(entry_path: &Path, entry: &CacheEntry) -> Result<(), Box<dyn Error>> {
+    let temp_file_path = entry_path.join(format!("{}.tmp", ENTRY_FILE_NAME));
+    fs::write(&temp_file_path, serde_json::to_string(entry)?)?;
+    fs::rename(temp_file_path, entry_path.join(ENTRY_FILE_NAME))?;
+    Ok(())
+}
+
+fn get_key(value: &str) -> String {
+    Sha256::digest(value.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use tempfile::TempDir;
+
+    fn cache_config(cache: &TempDir) -> Config {
+        Config { download_cache_path: Some(cache.path().to_path_buf()), ..Config::default() }
+    }
+
+    fn store(url: &str, content: &str, last_used: u64) -> String {
+        let download = TempDir::new().unwrap();
+        let download_path = download.path().join("archive.tar.xz");
+        fs::write(&download_path, content).unwrap();
+        let slot = lock_slot(url, None).unwrap().unwrap();
+        slot.store_file(&download_path).unwrap();
+        let mut entry = read_entry(&slot.path).unwrap().unwrap();
+        entry.last_used = last_used;
+        write_entry(&slot.path, &entry).unwrap();
+        entry.key
+    }
+
+    fn get_urls(entries: &[CacheEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.url.as_str()).collect()
+    }
+
+    #[test]
+    fn prune_removes_the_least_recently_used_entries_first() {
+        let cache = TempDir::new().unwrap();
+        let _config = test_support::set_test_config(cache_config(&cache));
+        store("https://example.com/b", "0123456789", 2);
+        store("https://example.com/a", "0123456789", 1);
+        store("https://example.com/c", "0123456789", 3);
+
+        assert_eq!(get_urls(&list_entries().unwrap()), ["https://example.com/c", "https://example.com/b", "https://example.com/a"]);
+        assert_eq!(get_urls(&prune(20).unwrap()), ["https://example.com/a"]);
+        assert_eq!(get_urls(&prune(10).unwrap()), ["https://example.com/b"]);
+        assert_eq!(get_urls(&list_entries().unwrap()), ["https://example.com/c"]);
+    }
+
+    #[test]
+    fn prune_skips_locked_entries() {
+        let cache = TempDir::new().unwrap();
+        let _config = test_support::set_test_config(cache_config(&cache));
+        store("https://example.com/a", "0123456789", 1);
+        store("https://example.com/b", "0123456789", 2);
+
+        let slot = lock_slot("https://example.com/a", None).unwrap().unwrap();
+        assert_eq!(get_urls(&clear().unwrap()), ["https://example.com/b"]);
+        assert!(slot.get_file().unwrap().is_some());
+        drop(slot);
+        assert_eq!(get_urls(&clear().unwrap()), ["https://example.com/a"]);
+    }
+
+    #[test]
+    fn prune_removes_orphaned_folders_and_stale_locks() {
+        let cache = TempDir::new().unwrap();
+        let _config = test_support::set_test_config(cache_config(&cache));
+        let key = store("https://example.com/a", "0123456789", 1);
+        let orphan_path = cache.path().join(ENTRIES_FOLDER_NAME).join("orphan");
+        fs::create_dir_all(&orphan_path).unwrap();
+        fs::write(orphan_path.join("archive.tar.xz"), "01234").unwrap();
+        let unreadable_path = cache.path().join(ENTRIES_FOLDER_NAME).join("unreadable");
+        fs::create_dir_all(&unreadable_path).unwrap();
+        fs::write(unreadable_path.join(ENTRY_FILE_NAME), "{").unwrap();
+        fs::write(get_lock_path(cache.path(), "stale"), "").unwrap();
+
+        assert_eq!(get_urls(&list_entries().unwrap()), ["https://example.com/a"]);
+        let mut removed: Vec<(String, u64)> = prune(u64::MAX).unwrap().into_iter().map(|entry| (entry.key, entry.size)).collect();
+        removed.sort();
+        assert_eq!(removed, [(String::from("orphan"), 5), (String::from("unreadable"), 1)]);
+        assert!(!orphan_path.exists());
+        assert!(!get_lock_path(cache.path(), "stale").exists());
+        assert!(get_lock_path(cache.path(), &key).exists());
+        assert_eq!(get_urls(&list_entries().unwrap()), ["https://example.com/a"]);
+    }
+
+    #[test]
+    fn hits_are_checked_against_the_stored_size() {
+        let cache = TempDir::new().unwrap();
+        let _config = test_support::set_test_config(cache_config(&cache));
+        store("https://example.com/a", "0123456789", 1);
+
+        let slot = lock_slot("https://example.com/a", None).unwrap().unwrap();
+        let file_path = slot.get_file().unwrap().unwrap();
+        assert!(read_entry(&slot.path).unwrap().unwrap().last_used > 1);
+        assert!(!slot.path.join(format!("{}.tmp", ENTRY_FILE_NAME)).exists());
+
+        fs::write(&file_path, "01234").unwrap();
+        assert!(slot.get_file().unwrap().is_none());
+        assert!(!slot.path.exists());
+    }
+}
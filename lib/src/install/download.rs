@@ -1,47 +1,120 @@
-use reqwest::header::{HeaderValue, CONTENT_LENGTH, RANGE};
-use reqwest::StatusCode;
+use crate::progress::ProgressEvent;
+use crate::network::RequestTarget;
+use crate::{config, network, progress};
+use fs4::fs_std::FileExt;
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, RANGE};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs;
 use std::fs::File;
+use std::io;
+use std::io::{Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
 
-const CHUNK_SIZE: u32 = 10485760;
+#[cfg(not(test))]
+const CHUNK_SIZE: u64 = 10485760;
+// Small chunks let the tests cover several of them without serving megabytes.
+#[cfg(test)]
+const CHUNK_SIZE: u64 = 1024;
+const CHUNK_RETRIES: u32 = 3;
+const PROGRESS_STEP: u64 = 1048576;
+static PART_FILE_SUFFIX: &str = ".part";
+static PROGRESS_FILE_SUFFIX: &str = ".progress";
 
-struct PartialRangeIter {
+struct Chunk {
+    index: usize,
     start: u64,
     end: u64,
-    buffer_size: u32,
 }
 
-struct PartialRangeIterHeader {
-    header: HeaderValue,
-    bytes: u64,
+impl Chunk {
+    fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    fn range_header(&self) -> HeaderValue {
+        HeaderValue::from_str(&format!("bytes={}-{}", self.start, self.end)).expect("string provided by format!")
+    }
 }
 
-impl PartialRangeIter {
-    pub fn new(start: u64, end: u64, buffer_size: u32) -> Result<Self, Box<dyn Error>> {
-        if buffer_size == 0 {
-            Err("invalid buffer_size, give a value greater than zero.")?;
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadProgress {
+    length: u64,
+    chunk_size: u64,
+    completed: Vec<bool>,
+}
+
+impl DownloadProgress {
+    fn new(length: u64) -> Self {
+        DownloadProgress {
+            length,
+            chunk_size: CHUNK_SIZE,
+            completed: vec![false; length.div_ceil(CHUNK_SIZE) as usize],
         }
-        Ok(PartialRangeIter { start, end, buffer_size })
-    }
-}
-
-impl Iterator for PartialRangeIter {
-    type Item = PartialRangeIterHeader;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.start > self.end {
-            None
-        } else {
-            let prev_start = self.start;
-            self.start += std::cmp::min(self.buffer_size as u64, self.end - self.start + 1);
-            Some(PartialRangeIterHeader {
-                header: HeaderValue::from_str(&format!("bytes={}-{}", prev_start, self.start - 1)).expect("string provided by format!"),
-                bytes: self.start,
-            })
+    }
+
+    fn get_chunk(&self, index: usize) -> Chunk {
+        let start = index as u64 * self.chunk_size;
+        Chunk {
+            index,
+            start,
+            end: (start + self.chunk_size).min(self.length) - 1,
         }
     }
+
+    fn completed_bytes(&self) -> u64 {
+        (0..self.completed.len()).filter(|index| self.completed[*index]).map(|index| self.get_chunk(index).len()).sum()
+    }
+}
+
+struct DownloadCounter<'a> {
+    id: &'a str,
+    size: u64,
+    downloaded: AtomicU64,
+}
+
+impl DownloadCounter<'_> {
+    // Reports once per PROGRESS_STEP bytes so reporters aren't flooded by small reads.
+    fn add(&self, bytes: u64) {
+        let downloaded = self.downloaded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if (downloaded - bytes) / PROGRESS_STEP != downloaded / PROGRESS_STEP || downloaded == self.size {
+            progress::report(ProgressEvent::DownloadProgress {
+                id: String::from(self.id),
+                downloaded,
+                size: self.size,
+            });
+        }
+    }
+
+    fn remove(&self, bytes: u64) {
+        self.downloaded.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+struct ProgressWriter<'a> {
+    file: File,
+    counter: &'a DownloadCounter<'a>,
+    written: u64,
+}
+
+impl Write for ProgressWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        self.counter.add(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
 }
 
 pub fn download(url: &str, module_id: &str, editor_path: impl AsRef<Path>) -> Result<PathBuf, Box<dyn Error>> {
@@ -50,40 +123,332 @@ pub fn download(url: &str, module_id: &str, editor_path: impl AsRef<Path>) -> Re
     download_path.push("downloads");
     fs::create_dir_all(&download_path)?;
 
-    let client = reqwest::blocking::Client::new();
-    let response = client.head(url).send()?;
+    let client = network::get_client()?;
+    let target = RequestTarget::new(url)?;
+    let response = target.send(&client, Method::HEAD, |request| request)?;
     let length = response.headers().get(CONTENT_LENGTH).ok_or("response doesn't include the content length")?;
     let length = u64::from_str(length.to_str()?).map_err(|_| "invalid Content-Length header")?;
+    let accepts_ranges = response.headers().get(ACCEPT_RANGES).is_some_and(|value| value.as_bytes() == b"bytes");
 
     let download_url = response.url();
-    let mut filename = download_url.path().split('/').last().unwrap();
+    let mut filename = download_url.path().split('/').next_back().unwrap();
     if filename.is_empty() {
         filename = module_id;
     }
-    let mut output_file_path = PathBuf::from(download_path);
+    let mut output_file_path = download_path;
     output_file_path.push(filename);
 
-    let mut output_file: File;
-    let mut start_byte = 0;
-    match output_file_path.exists() {
-        true => {
-            output_file = File::options().read(true).write(true).append(true).open(&output_file_path)?;
-            start_byte = output_file_path.metadata()?.len();
+    // Downloads are written to a part file and only renamed once complete, so a file with the final name is always whole.
+    if fs::metadata(&output_file_path).is_ok_and(|metadata| metadata.is_file() && metadata.len() == length) {
+        progress::report(ProgressEvent::DownloadCached { id: String::from(module_id) });
+        return Ok(output_file_path);
+    }
+    let part_file_path = get_suffixed_path(&output_file_path, PART_FILE_SUFFIX);
+
+    if !accepts_ranges || length == 0 {
+        progress::report(ProgressEvent::DownloadStarted { id: String::from(module_id), size: Some(length) });
+        let counter = DownloadCounter { id: module_id, size: length, downloaded: AtomicU64::new(0) };
+        download_whole_file(&client, &target, &part_file_path, &counter)?;
+        fs::rename(&part_file_path, &output_file_path)?;
+        progress::report(ProgressEvent::DownloadFinished { id: String::from(module_id) });
+        return Ok(output_file_path);
+    }
+
+    // The progress is recorded before the part file is preallocated, so a zero-filled part file is never mistaken for downloaded data.
+    let progress_file_path = get_suffixed_path(&output_file_path, PROGRESS_FILE_SUFFIX);
+    let download_progress = load_progress(&part_file_path, &progress_file_path, length)?;
+    write_progress(&progress_file_path, &download_progress)?;
+    let part_file = File::options().write(true).create(true).truncate(false).open(&part_file_path)?;
+    if part_file.metadata()?.len() != length {
+        part_file.allocate(length)?;
+        part_file.set_len(length)?;
+    }
+
+    let pending: Vec<Chunk> = (0..download_progress.completed.len())
+        .filter(|index| !download_progress.completed[*index])
+        .map(|index| download_progress.get_chunk(index))
+        .collect();
+    let connections = (config::get_config()?.download_connections.max(1) as usize).min(pending.len());
+    progress::report(ProgressEvent::DownloadStarted { id: String::from(module_id), size: Some(length) });
+    let counter = DownloadCounter { id: module_id, size: length, downloaded: AtomicU64::new(0) };
+    counter.add(download_progress.completed_bytes());
+    let pending = Mutex::new(pending.into_iter());
+    let download_progress = Mutex::new(download_progress);
+    let failed = AtomicBool::new(false);
+    let errors: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..connections {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let chunk = match pending.lock().unwrap().next() {
+                        None => return,
+                        Some(chunk) => chunk,
+                    };
+                    let result = download_chunk_with_retries(&client, &target, &part_file_path, &chunk, &counter).and_then(|_| {
+                        let mut download_progress = download_progress.lock().unwrap();
+                        download_progress.completed[chunk.index] = true;
+                        write_progress(&progress_file_path, &download_progress)
+                    });
+                    if let Err(err) = result {
+                        failed.store(true, Ordering::Relaxed);
+                        errors.lock().unwrap().push(err.to_string());
+                    }
+                }
+            });
         }
-        false => {
-            output_file = File::create(&output_file_path)?;
+    });
+
+    if let Some(err) = errors.into_inner().unwrap().into_iter().next() {
+        return Err(format!("Couldn't download {}: {}", module_id, err).into());
+    }
+    fs::rename(&part_file_path, &output_file_path)?;
+    remove_progress(&progress_file_path)?;
+    progress::report(ProgressEvent::DownloadFinished { id: String::from(module_id) });
+    Ok(output_file_path)
+}
+
+fn download_chunk_with_retries(client: &Client, target: &RequestTarget, file_path: &Path, chunk: &Chunk, counter: &DownloadCounter) -> Result<(), Box<dyn Error>> {
+    let mut attempt = 1;
+    loop {
+        match download_chunk(client, target, file_path, chunk, counter) {
+            Ok(()) => return Ok(()),
+            Err(_) if attempt < CHUNK_RETRIES => attempt += 1,
+            Err(err) => return Err(err),
         }
     }
+}
+
+fn download_chunk(client: &Client, target: &RequestTarget, file_path: &Path, chunk: &Chunk, counter: &DownloadCounter) -> Result<(), Box<dyn Error>> {
+    let mut response = target.send(client, Method::GET, |request| request.header(RANGE, chunk.range_header()))?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(format!("Unexpected server response {} for bytes {}-{}", response.status(), chunk.start, chunk.end).into());
+    }
+    let mut file = File::options().write(true).open(file_path)?;
+    file.seek(SeekFrom::Start(chunk.start))?;
+    let mut writer = ProgressWriter { file, counter, written: 0 };
+    let result = io::copy(&mut response, &mut writer);
+    if result.is_err() || writer.written != chunk.len() {
+        counter.remove(writer.written);
+        return Err(format!("Received {} of {} bytes for bytes {}-{}", writer.written, chunk.len(), chunk.start, chunk.end).into());
+    }
+    writer.file.sync_data()?;
+    Ok(())
+}
 
-    for range in PartialRangeIter::new(start_byte, length - 1, CHUNK_SIZE)? {
-        println!("Downloading {}: {:.2}%.", module_id, (range.bytes as f32) / (length as f32) * 100.0);
-        let mut response = client.get(url).header(RANGE, range.header).send()?;
+fn download_whole_file(client: &Client, target: &RequestTarget, file_path: &Path, counter: &DownloadCounter) -> Result<(), Box<dyn Error>> {
+    let mut response = target.send(client, Method::GET, |request| request)?;
+    if response.status() != StatusCode::OK {
+        return Err("Unexpected server response".into());
+    }
+    let mut writer = ProgressWriter { file: File::create(file_path)?, counter, written: 0 };
+    let result = io::copy(&mut response, &mut writer);
+    if result.is_err() || writer.written != counter.size {
+        return Err(format!("Received {} of {} bytes", writer.written, counter.size).into());
+    }
+    writer.file.sync_data()?;
+    Ok(())
+}
+
+fn get_suffixed_path(file_path: &Path, suffix: &str) -> PathBuf {
+    let mut suffixed_path = file_path.as_os_str().to_os_string();
+    suffixed_path.push(suffix);
+    PathBuf::from(suffixed_path)
+}
 
-        let status = response.status();
-        if !(status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT) {
-            return Err("Unexpected server response".into());
+/// Only the chunks recorded in the progress file are trusted. Without a matching record the part file starts over.
+fn load_progress(part_file_path: &Path, progress_file_path: &Path, length: u64) -> Result<DownloadProgress, Box<dyn Error>> {
+    let progress = DownloadProgress::new(length);
+    let recorded: Option<DownloadProgress> = fs::read_to_string(progress_file_path).ok().and_then(|contents| serde_json::from_str(&contents).ok());
+    let part_file_length = fs::metadata(part_file_path).map(|metadata| metadata.len()).ok();
+    match recorded {
+        Some(recorded)
+            if recorded.length == progress.length && recorded.chunk_size == progress.chunk_size && recorded.completed.len() == progress.completed.len()
+                && part_file_length == Some(length) =>
+        {
+            Ok(recorded)
+        }
+        _ => {
+            if part_file_length.is_some() {
+                fs::remove_file(part_file_path)?;
+            }
+            Ok(progress)
         }
-        std::io::copy(&mut response, &mut output_file)?;
     }
-    Ok(output_file_path)
+}
+
+fn write_progress(progress_file_path: &Path, progress: &DownloadProgress) -> Result<(), Box<dyn Error>> {
+    let temp_path = progress_file_path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_string(progress)?)?;
+    fs::rename(temp_path, progress_file_path)?;
+    Ok(())
+}
+
+fn remove_progress(progress_file_path: &Path) -> Result<(), Box<dyn Error>> {
+    if progress_file_path.exists() {
+        fs::remove_file(progress_file_path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const DATA_LENGTH: usize = 3000;
+
+    fn get_data() -> Vec<u8> {
+        (0..DATA_LENGTH).map(|index| (index % 251) as u8).collect()
+    }
+
+    fn start_range_server() -> TestServer {
+        let data = get_data();
+        TestServer::start(move |request| test_support::serve_range(request, &data))
+    }
+
+    fn set_download_config() -> std::sync::MutexGuard<'static, ()> {
+        test_support::set_test_config(Config { download_connections: 2, ..Config::default() })
+    }
+
+    fn get_requested_ranges(server: &TestServer) -> Vec<String> {
+        let mut ranges: Vec<String> = server.take_requests().iter().filter(|request| request.method == "GET").filter_map(|request| request.header("range").map(String::from)).collect();
+        ranges.sort();
+        ranges
+    }
+
+    fn write_partial_download(editor_path: &Path, completed: Vec<bool>) {
+        let downloads_path = editor_path.join("downloads");
+        fs::create_dir_all(&downloads_path).unwrap();
+        let mut part = vec![0; DATA_LENGTH];
+        part[..CHUNK_SIZE as usize].copy_from_slice(&get_data()[..CHUNK_SIZE as usize]);
+        fs::write(downloads_path.join("data.bin.part"), part).unwrap();
+        let progress = DownloadProgress { length: DATA_LENGTH as u64, chunk_size: CHUNK_SIZE, completed };
+        fs::write(downloads_path.join("data.bin.progress"), serde_json::to_string(&progress).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn downloads_chunks_in_parallel() {
+        let server = start_range_server();
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+
+        let path = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), get_data());
+        assert_eq!(get_requested_ranges(&server), ["bytes=0-1023", "bytes=1024-2047", "bytes=2048-2999"]);
+        assert!(!get_suffixed_path(&path, PART_FILE_SUFFIX).exists());
+        assert!(!get_suffixed_path(&path, PROGRESS_FILE_SUFFIX).exists());
+    }
+
+    #[test]
+    fn interrupted_chunks_are_downloaded_again() {
+        let data = get_data();
+        let broken = Arc::new(AtomicBool::new(true));
+        let server_broken = broken.clone();
+        let server = TestServer::start(move |request| {
+            let response = test_support::serve_range(request, &data);
+            match request.header("range") {
+                Some("bytes=1024-2047") if server_broken.load(Ordering::SeqCst) => response.truncated(100),
+                _ => response,
+            }
+        });
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+        let url = server.url("/files/data.bin");
+
+        let err = download(&url, "module", editor.path()).unwrap_err();
+        assert!(err.to_string().contains("bytes 1024-2047"), "{}", err);
+        let progress: DownloadProgress = serde_json::from_str(&fs::read_to_string(editor.path().join("downloads").join("data.bin.progress")).unwrap()).unwrap();
+        assert!(!progress.completed[1]);
+        assert!(!editor.path().join("downloads").join("data.bin").exists());
+
+        broken.store(false, Ordering::SeqCst);
+        server.take_requests();
+        let path = download(&url, "module", editor.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), get_data());
+        // Only the chunks missing from the progress file are requested again.
+        let expected_ranges: Vec<String> = (0..progress.completed.len())
+            .filter(|index| !progress.completed[*index])
+            .map(|index| progress.get_chunk(index).range_header().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(get_requested_ranges(&server), expected_ranges);
+    }
+
+    #[test]
+    fn resumes_from_the_recorded_progress() {
+        let server = start_range_server();
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+        write_partial_download(editor.path(), vec![true, false, false]);
+
+        let path = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), get_data());
+        assert_eq!(get_requested_ranges(&server), ["bytes=1024-2047", "bytes=2048-2999"]);
+    }
+
+    #[test]
+    fn part_files_without_progress_start_over() {
+        let server = start_range_server();
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+        write_partial_download(editor.path(), vec![true, true, true]);
+        fs::remove_file(editor.path().join("downloads").join("data.bin.progress")).unwrap();
+
+        let path = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), get_data());
+        assert_eq!(get_requested_ranges(&server).len(), 3);
+    }
+
+    #[test]
+    fn complete_downloads_are_reused() {
+        let server = start_range_server();
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+        fs::create_dir_all(editor.path().join("downloads")).unwrap();
+        fs::write(editor.path().join("downloads").join("data.bin"), get_data()).unwrap();
+        let reporter = test_support::record_progress();
+
+        let path = download(&server.url("/files/data.bin"), "complete-module", editor.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), get_data());
+        assert!(server.take_requests().iter().all(|request| request.method == "HEAD"));
+        let events: Vec<String> = reporter.take_events().into_iter().filter(|event| event.contains("\"complete-module\"")).collect();
+        assert_eq!(events, ["DownloadCached { id: \"complete-module\" }"]);
+    }
+
+    #[test]
+    fn downloads_whole_files_without_range_support() {
+        let server = TestServer::start(|_| TestResponse::new(200, get_data()));
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+
+        let path = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), get_data());
+        assert!(server.take_requests().iter().all(|request| request.header("range").is_none()));
+    }
+
+    #[test]
+    fn truncated_whole_files_are_errors() {
+        let server = TestServer::start(|_| TestResponse::new(200, get_data()).truncated(1000));
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+
+        let err = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap_err();
+        assert!(err.to_string().contains("Received 1000 of 3000 bytes"), "{}", err);
+        assert!(!editor.path().join("downloads").join("data.bin").exists());
+    }
+
+    #[test]
+    fn ignored_ranges_are_errors() {
+        let server = TestServer::start(|_| TestResponse::new(200, get_data()).with_header("Accept-Ranges", "bytes"));
+        let _config = set_download_config();
+        let editor = TempDir::new().unwrap();
+
+        let err = download(&server.url("/files/data.bin"), "module", editor.path()).unwrap_err();
+        assert!(err.to_string().contains("Unexpected server response 200"), "{}", err);
+        assert!(!editor.path().join("downloads").join("data.bin").exists());
+    }
 }
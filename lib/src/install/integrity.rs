@@ -0,0 +1,131 @@
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const READ_BUFFER_SIZE: usize = 1048576;
+
+pub fn verify_file(file_path: impl AsRef<Path>, integrity: &str) -> Result<bool, Box<dyn Error>> {
+    let mut supported_hash_found = false;
+    for hash in integrity.split_whitespace() {
+        let (algorithm, expected) = match hash.split_once('-') {
+            Some(value) => value,
+            None => continue,
+        };
+        let digest = match algorithm {
+            "sha1" => compute_digest::<Sha1>(file_path.as_ref())?,
+            "sha256" => compute_digest::<Sha256>(file_path.as_ref())?,
+            "sha384" => compute_digest::<Sha384>(file_path.as_ref())?,
+            "sha512" => compute_digest::<Sha512>(file_path.as_ref())?,
+            _ => continue,
+        };
+        supported_hash_found = true;
+        if digest_matches(&digest, expected) {
+            return Ok(true);
+        }
+    }
+    if !supported_hash_found {
+        return Err(format!("Unsupported integrity value \"{}\"", integrity).into());
+    }
+    Ok(false)
+}
+
+pub fn get_file_integrity(file_path: impl AsRef<Path>) -> Result<String, Box<dyn Error>> {
+    let digest = compute_digest::<Sha256>(file_path.as_ref())?;
+    Ok(format!("sha256-{}", BASE64_STANDARD.encode(digest)))
+}
+
+fn compute_digest<D: Digest>(file_path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut file = File::open(file_path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+// Unity encodes some hashes as the base64 of the hex digest instead of the raw digest bytes.
+fn digest_matches(digest: &[u8], expected: &str) -> bool {
+    let expected = match BASE64_STANDARD.decode(expected) {
+        Ok(expected) => expected,
+        Err(_) => return false,
+    };
+    if expected == digest {
+        return true;
+    }
+    let hex_digest = digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    String::from_utf8(expected).is_ok_and(|expected| expected.trim().eq_ignore_ascii_case(&hex_digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    static CONTENTS: &[u8] = b"wrum";
+
+    fn write_file(folder: &TempDir) -> std::path::PathBuf {
+        let file_path = folder.path().join("download.bin");
+        fs::write(&file_path, CONTENTS).unwrap();
+        file_path
+    }
+
+    fn get_integrity<D: Digest>(algorithm: &str) -> String {
+        format!("{}-{}", algorithm, BASE64_STANDARD.encode(D::digest(CONTENTS)))
+    }
+
+    #[test]
+    fn verifies_every_supported_algorithm() {
+        let folder = TempDir::new().unwrap();
+        let file_path = write_file(&folder);
+        for integrity in [get_integrity::<Sha1>("sha1"), get_integrity::<Sha256>("sha256"), get_integrity::<Sha384>("sha384"), get_integrity::<Sha512>("sha512")] {
+            assert!(verify_file(&file_path, &integrity).unwrap(), "{}", integrity);
+        }
+        assert!(!verify_file(&file_path, &format!("sha256-{}", BASE64_STANDARD.encode(Sha256::digest(b"other")))).unwrap());
+    }
+
+    #[test]
+    fn any_matching_hash_of_a_list_is_enough() {
+        let folder = TempDir::new().unwrap();
+        let file_path = write_file(&folder);
+        let mismatch = format!("sha512-{}", BASE64_STANDARD.encode(Sha512::digest(b"other")));
+        assert!(verify_file(&file_path, &format!("md5-abc {} {}", mismatch, get_integrity::<Sha256>("sha256"))).unwrap());
+        assert!(!verify_file(&file_path, &format!("md5-abc {}", mismatch)).unwrap());
+    }
+
+    #[test]
+    fn unsupported_integrity_is_an_error() {
+        let folder = TempDir::new().unwrap();
+        let file_path = write_file(&folder);
+        assert!(verify_file(&file_path, "md5-abc").is_err());
+        assert!(verify_file(&file_path, "").is_err());
+    }
+
+    #[test]
+    fn matches_hex_digests_encoded_in_base64() {
+        let digest = Sha256::digest(CONTENTS);
+        let hex_digest: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        assert!(digest_matches(&digest, &BASE64_STANDARD.encode(digest)));
+        assert!(digest_matches(&digest, &BASE64_STANDARD.encode(&hex_digest)));
+        assert!(digest_matches(&digest, &BASE64_STANDARD.encode(hex_digest.to_uppercase())));
+        assert!(!digest_matches(&digest, &BASE64_STANDARD.encode(b"0123")));
+        assert!(!digest_matches(&digest, "not base64!"));
+    }
+
+    #[test]
+    fn file_integrity_is_sha256() {
+        let folder = TempDir::new().unwrap();
+        let file_path = write_file(&folder);
+        assert_eq!(get_file_integrity(&file_path).unwrap(), get_integrity::<Sha256>("sha256"));
+    }
+}
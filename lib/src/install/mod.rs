@@ -1,5 +1,6 @@
 use crate::live_api::release_info::FileType;
-use crate::system_info;
+use crate::progress::ProgressEvent;
+use crate::{config, progress, system_info};
 use serde_json::Value;
 use std::error::Error;
 use std::{fs};
@@ -7,10 +8,13 @@ use std::fs::File;
 use std::path::{Path, PathBuf};
 use fs4::fs_std::FileExt;
 
+pub mod cache;
 mod download;
-mod unpack;
+pub mod integrity;
+pub(crate) mod unpack;
 
 static CONFIG_FILE_NAME: &str = "secondaryInstallPath.json";
+const INTEGRITY_ATTEMPTS: u32 = 2;
 
 pub fn get_install_lock(id: &str, editor_path: &Path) -> Result<File, Box<dyn Error>> {
     fs::create_dir_all(editor_path)?;
@@ -20,33 +24,105 @@ pub fn get_install_lock(id: &str, editor_path: &Path) -> Result<File, Box<dyn Er
         File::create(&lock_file_path)?;
     }
     let lock_file = File::open(&lock_file_path)?;
-    if let Err(_) = lock_file.try_lock_exclusive() {
-        println!("Another process is already installing \"{}\". Waiting...", id);
+    if lock_file.try_lock_exclusive().is_err() {
+        progress::report(ProgressEvent::WaitingForLock { id: String::from(id) });
     }
     lock_file.lock_exclusive()?;
     Ok(lock_file)
 }
 
+pub fn try_get_install_locks(editor_path: &Path) -> Result<Option<Vec<File>>, Box<dyn Error>> {
+    let mut lock_files = Vec::new();
+    for child in fs::read_dir(editor_path)? {
+        let lock_file_path = child?.path();
+        if !is_install_lock_path(&lock_file_path) {
+            continue;
+        }
+        let lock_file = File::open(&lock_file_path)?;
+        if lock_file.try_lock_exclusive().is_err() {
+            return Ok(None);
+        }
+        lock_files.push(lock_file);
+    }
+    Ok(Some(lock_files))
+}
+
+pub fn is_install_lock_path(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    file_name.starts_with('.') && file_name.ends_with(".lock")
+}
+
 pub fn release_install_lock(lock_file: File) -> Result<(), Box<dyn Error>> {
     lock_file.unlock()?;
     Ok(())
 }
 
-pub fn install(url: &str, id: &str, editor_path: &Path, module_type: FileType, destination: &str,
-               rename_from: &str, rename_to: &str) -> Result<(), Box<dyn Error>> {
-    let download_path = download::download(url, id, editor_path)?;
+#[allow(clippy::too_many_arguments)]
+pub fn install(url: &str, integrity: Option<&str>, id: &str, editor_path: &Path, module_type: FileType, destination: &str,
+               rename_from: &str, rename_to: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let cache_slot = match cache::lock_slot(url, integrity)? {
+        None => {
+            let download_path = download_verified(url, integrity, id, editor_path)?;
+            let files = install_file(&download_path, id, editor_path, module_type, destination, rename_from, rename_to)?;
+            fs::remove_file(download_path)?;
+            return Ok(files);
+        }
+        Some(cache_slot) => cache_slot,
+    };
+    let cached_file_path = match cache_slot.get_file()? {
+        Some(cached_file_path) => {
+            progress::report(ProgressEvent::DownloadCached { id: String::from(id) });
+            cached_file_path
+        }
+        None => {
+            let cached_file_path = cache_slot.store_file(&download_verified(url, integrity, id, editor_path)?)?;
+            if let Err(err) = cache::prune_to_configured_size() {
+                progress::warn(format!("failed to prune the download cache: {}", err));
+            }
+            cached_file_path
+        }
+    };
+    install_file(&cached_file_path, id, editor_path, module_type, destination, rename_from, rename_to)
+}
+
+fn download_verified(url: &str, integrity: Option<&str>, id: &str, editor_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let integrity = match integrity {
+        Some(integrity) if !integrity.is_empty() && !config::get_config()?.skip_integrity => integrity,
+        _ => return download::download(url, id, editor_path),
+    };
+    let mut attempt = 1;
+    loop {
+        let download_path = download::download(url, id, editor_path)?;
+        progress::report(ProgressEvent::VerifyStarted { id: String::from(id) });
+        match integrity::verify_file(&download_path, integrity) {
+            Ok(true) => return Ok(download_path),
+            Ok(false) => fs::remove_file(&download_path)?,
+            Err(err) => return Err(format!("Couldn't verify the download of {}: {}. Use --skip-integrity to install it unverified", id, err).into()),
+        }
+        if attempt == INTEGRITY_ATTEMPTS {
+            return Err(format!("The download of {} doesn't match its integrity {}. Use --skip-integrity if the mirror re-packs archives", id, integrity).into());
+        }
+        progress::report(ProgressEvent::IntegrityMismatch { id: String::from(id) });
+        attempt += 1;
+    }
+}
 
-    println!("Unpacking {}.", id);
-    unpack::unpack(module_type.clone(), &download_path, get_in_editor_path(editor_path.to_str().unwrap(), destination))?;
+pub fn install_file(file_path: &Path, id: &str, editor_path: &Path, module_type: FileType, destination: &str,
+                    rename_from: &str, rename_to: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    progress::report(ProgressEvent::UnpackStarted { id: String::from(id) });
+    let mut files = unpack::unpack(module_type.clone(), file_path, get_in_editor_path(editor_path.to_str().unwrap(), destination))?;
     if !rename_from.is_empty() && !rename_to.is_empty() {
         let rename_from = get_in_editor_path(editor_path.to_str().unwrap(), rename_from);
         let rename_to = get_in_editor_path(editor_path.to_str().unwrap(), rename_to);
-        unpack::move_files(rename_from, rename_to)?;
+        unpack::move_files(&rename_from, &rename_to)?;
+        files = files.into_iter().map(|file| match file.strip_prefix(&rename_from) {
+            Ok(relative_path) => rename_to.join(relative_path),
+            Err(_) => file,
+        }).collect();
     }
 
-    fs::remove_file(download_path)?;
-    println!("{} successfully installed.", id);
-    Ok(())
+    progress::report(ProgressEvent::UnpackFinished { id: String::from(id) });
+    Ok(files)
 }
 
 pub fn get_install_path() -> Result<PathBuf, Box<dyn Error>> {
@@ -90,3 +166,26 @@ pub fn get_secondary_install_path() -> Result<Option<PathBuf>, Box<dyn Error>> {
 fn get_in_editor_path(editor_path: &str, relative_path: &str) -> PathBuf {
     PathBuf::from(relative_path.replace("{UNITY_PATH}", editor_path))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use tempfile::TempDir;
+
+    #[test]
+    fn unverifiable_downloads_fail_unless_integrity_is_skipped() {
+        let server = TestServer::start(|_| TestResponse::new(200, "archive"));
+        let editor = TempDir::new().unwrap();
+        let url = server.url("/archive.tar.xz");
+        let _config = test_support::set_test_config(Config::default());
+
+        let err = download_verified(&url, Some("md5-abc"), "module", editor.path()).unwrap_err();
+        assert!(err.to_string().contains("Couldn't verify the download of module"), "{}", err);
+
+        config::set_config(Config { skip_integrity: true, ..Config::default() });
+        assert!(download_verified(&url, Some("md5-abc"), "module", editor.path()).is_ok());
+    }
+}
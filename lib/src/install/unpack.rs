@@ -15,7 +15,7 @@ use std::{fs, io};
 use tempfile::TempDir;
 use xz2::read::XzDecoder;
 
-pub fn unpack(module_type: FileType, file_path: impl AsRef<Path>, target_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+pub fn unpack(module_type: FileType, file_path: impl AsRef<Path>, target_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let file_path = file_path.as_ref();
     if !target_path.as_ref().exists() {
         fs::create_dir_all(&target_path)?;
@@ -38,7 +38,7 @@ pub fn unpack(module_type: FileType, file_path: impl AsRef<Path>, target_path: i
         }
     }
 
-    match module_type {
+    let files = match module_type {
         FileType::TEXT => unimplemented!(),
         FileType::TAR_GZ => unpack_tar_gz(file_path, target_path)?,
         FileType::TAR_XZ => unpack_tar_xz(file_path, target_path)?,
@@ -52,9 +52,9 @@ pub fn unpack(module_type: FileType, file_path: impl AsRef<Path>, target_path: i
         FileType::MD => unimplemented!(),
         FileType::PDF => unimplemented!(),
         FileType::Other(_) => unimplemented!(),
-    }
+    };
 
-    Ok(())
+    Ok(files)
 }
 
 pub fn move_files(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()> {
@@ -62,69 +62,77 @@ pub fn move_files(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()
     fs::remove_dir_all(from)?;
     Ok(())
 }
-fn copy_files(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()> {
+fn copy_files(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
     fs::create_dir_all(&to)?;
+    let mut files = Vec::new();
     for entry in fs::read_dir(from)? {
         let entry = entry?;
         let entry_type = entry.file_type()?;
+        let target_path = to.as_ref().join(entry.file_name());
         if entry_type.is_dir() {
-            copy_files(entry.path(), to.as_ref().join(entry.file_name()))?;
+            files.append(&mut copy_files(entry.path(), target_path)?);
+            continue;
         } else if entry_type.is_symlink() {
-            std::os::unix::fs::symlink(fs::read_link(entry.path())?, to.as_ref().join(entry.file_name()))?;
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target_path)?;
         } else {
-            fs::copy(entry.path(), to.as_ref().join(entry.file_name()))?;
+            fs::copy(entry.path(), &target_path)?;
         }
+        files.push(target_path);
     }
-    Ok(())
+    Ok(files)
 }
 
-fn unpack_po(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_po(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     fs::create_dir_all(&destination_folder_path)?;
 
     let mut target_path = PathBuf::new();
     target_path.push(destination_folder_path);
     target_path.push(file_path.as_ref().file_name().unwrap());
-    fs::copy(&file_path, target_path)?;
-    fs::remove_file(file_path)?;
-    Ok(())
+    fs::copy(&file_path, &target_path)?;
+    Ok(vec![target_path])
 }
 
-fn unpack_zip(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_zip(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let zip_file = fs::File::open(file_path)?;
     let mut zip = zip::ZipArchive::new(zip_file)?;
-    zip.extract(destination_folder_path)?;
-    Ok(())
+    zip.extract(&destination_folder_path)?;
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index_raw(index)?;
+        if let Some(entry_path) = entry.enclosed_name().filter(|_| !entry.is_dir()) {
+            files.push(destination_folder_path.as_ref().join(entry_path));
+        }
+    }
+    Ok(files)
 }
 
-fn unpack_dmg(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_dmg(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let dmg = Attach::new(file_path.as_ref()).with()?;
     let mount_path = &dmg.mount_point;
+    let re = Regex::new(r"/visual\s?studio.*\.app$/i")?;
     for entry in fs::read_dir(mount_path)? {
         let entry = entry?;
         let path = entry.path();
         if path.extension() != Some(OsStr::new("app")) {
             continue;
         }
-        let re = Regex::new(r"/visual\s?studio.*\.app$/i")?;
         let mut target_path = PathBuf::new();
         target_path.push(destination_folder_path);
         if re.is_match(path.to_str().unwrap()) {
             target_path.push(path.file_name().unwrap());
         }
-        copy_files(path, target_path)?;
-        break;
+        return Ok(copy_files(path, target_path)?);
     }
 
-    Ok(())
+    Ok(Vec::new())
 }
 
-fn unpack_tar_gz(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_tar_gz(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let bytes = decompress_gzip(file_path)?;
-    unpack_tar(&bytes, destination_folder_path)?;
-    Ok(())
+    unpack_tar(&bytes, destination_folder_path)
 }
 
-fn unpack_tar_xz(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_tar_xz(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let bytes = decompress_xz(file_path)?;
     let mut destination_folder_path = PathBuf::from(destination_folder_path.as_ref());
     let playback_engines_path = ["Editor", "Data", "PlaybackEngines"].iter().collect::<PathBuf>();
@@ -136,29 +144,31 @@ fn unpack_tar_xz(file_path: impl AsRef<Path>, destination_folder_path: impl AsRe
         destination_folder_path.pop();
         destination_folder_path.pop();
     }
-    unpack_tar(&bytes, destination_folder_path)?;
-    Ok(())
+    unpack_tar(&bytes, destination_folder_path)
 }
 
-fn unpack_tar(tar_bytes: &[u8], destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
-    let mut archive = tar::Archive::new(&*tar_bytes);
+fn unpack_tar(tar_bytes: &[u8], destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut archive = tar::Archive::new(tar_bytes);
+    let mut files = Vec::new();
     for entry in archive.entries()? {
         let mut entry = entry?;
         let mut entry_dest_path = PathBuf::new();
         entry_dest_path.push(&destination_folder_path);
         entry_dest_path.push(entry.path()?);
-        entry.unpack(entry_dest_path)?;
+        entry.unpack(&entry_dest_path)?;
+        if !entry.header().entry_type().is_dir() {
+            files.push(entry_dest_path);
+        }
     }
-    Ok(())
+    Ok(files)
 }
 
-fn unpack_pkg(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_pkg(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let temp_dir = TempDir::new().unwrap();
     unpack_xar(file_path, temp_dir.path())?;
     let payload_path = find_payload_file(temp_dir.path())?;
     let gzip_bytes = decompress_gzip(payload_path)?;
-    unpack_cpio(&gzip_bytes, destination_folder_path)?;
-    Ok(())
+    unpack_cpio(&gzip_bytes, destination_folder_path)
 }
 
 fn unpack_xar(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<Path>) -> XarResult<()> {
@@ -167,22 +177,25 @@ fn unpack_xar(file_path: impl AsRef<Path>, destination_folder_path: impl AsRef<P
     xar.unpack(destination_folder_path)
 }
 
-fn unpack_cpio(bytes: &[u8], destination_folder_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+fn unpack_cpio(bytes: &[u8], destination_folder_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
     for entry in cpio_reader::iter_files(bytes) {
         let mut entry_dest_path = PathBuf::new();
         entry_dest_path.push(&destination_folder_path);
         entry_dest_path.push(entry.name());
         if entry.mode().contains(Mode::DIRECTORY) {
             fs::create_dir_all(entry_dest_path)?;
+            continue;
         } else if entry.mode().contains(Mode::SYMBOLIK_LINK) {
             std::os::unix::fs::symlink(String::from_utf8(entry.file().into())?, &entry_dest_path)?;
         } else {
             fs::write(&entry_dest_path, entry.file())?;
             fs::set_permissions(&entry_dest_path, Permissions::from_mode(entry.mode().bits()))?;
         }
+        files.push(entry_dest_path);
     }
 
-    Ok(())
+    Ok(files)
 }
 
 fn decompress_xz(file_path: impl AsRef<Path>) -> Result<Vec<u8>, Box<dyn Error>> {
@@ -1,8 +1,20 @@
+pub mod config;
+pub mod disk_usage;
 pub mod editors;
+pub mod hub;
 pub mod install;
 pub mod live_api;
 pub mod modules;
+pub mod network;
 pub mod system_info;
+pub mod progress;
 pub mod projects;
+pub mod templates;
 pub mod workarounds;
 pub mod license;
+pub mod release_notes;
+pub mod verify;
+pub mod version;
+
+#[cfg(test)]
+mod test_support;
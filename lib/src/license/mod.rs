@@ -5,7 +5,8 @@ use std::fs;
 use std::fs::File;
 use std::path::{Path, PathBuf};
 
-pub fn lock_license(license_username: &str) -> Result<File, Box<dyn Error>> {
+/// Returns the lock file held for the duration of the license use, along with its path.
+pub fn lock_license(license_username: &str) -> Result<(File, PathBuf), Box<dyn Error>> {
     let license_path = get_license_path();
     let common_lock_file = get_common_lock_file(&license_path)?;
     common_lock_file.lock_exclusive()?;
@@ -14,8 +15,7 @@ pub fn lock_license(license_username: &str) -> Result<File, Box<dyn Error>> {
     let process_lock_file = File::open(&process_lock_path)?;
     process_lock_file.lock_exclusive()?;
     common_lock_file.unlock()?;
-    println!("{}", process_lock_path.display());
-    Ok(process_lock_file)
+    Ok((process_lock_file, process_lock_path))
 }
 
 pub fn release_license(license_username: &str, process_lock_file: File) -> Result<bool, Box<dyn Error>>{
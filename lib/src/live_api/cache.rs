@@ -0,0 +1,140 @@
+use crate::{progress, system_info};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static CACHE_FILE_NAME: &str = "releases-cache.json";
+static CACHE_LOCK_FILE_NAME: &str = "releases-cache.lock";
+// Expired entries still answer --offline, so they are only dropped once they are this much older than the TTL.
+const OFFLINE_RETENTION: u64 = 30 * 24 * 3600;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    #[serde(rename = "fetchedAt")]
+    fetched_at: u64,
+    data: Value,
+}
+
+impl CacheEntry {
+    fn is_expired(&self, ttl: u64) -> bool {
+        self.fetched_at.saturating_add(ttl) < now()
+    }
+}
+
+pub fn read(key: &str, ttl: Option<u64>) -> Result<Option<Value>, Box<dyn Error>> {
+    let lock_file = open_lock_file()?;
+    lock_file.lock_shared()?;
+    let entries = load_entries()?;
+    match entries.get(key) {
+        None => Ok(None),
+        Some(entry) => match ttl {
+            Some(ttl) if entry.is_expired(ttl) => Ok(None),
+            _ => Ok(Some(entry.data.clone())),
+        },
+    }
+}
+
+pub fn write(key: &str, data: Value, ttl: u64) -> Result<(), Box<dyn Error>> {
+    // Concurrent processes merge their entries one at a time, and readers never see a half written file.
+    let lock_file = open_lock_file()?;
+    lock_file.lock_exclusive()?;
+    let mut entries = load_entries()?;
+    entries.retain(|_, entry| !entry.is_expired(ttl.saturating_add(OFFLINE_RETENTION)));
+    entries.insert(String::from(key), CacheEntry { fetched_at: now(), data });
+    let cache_file_path = get_cache_file_path();
+    let temp_file_path = cache_file_path.with_extension("json.tmp");
+    fs::write(&temp_file_path, serde_json::to_string(&entries)?)?;
+    fs::rename(temp_file_path, cache_file_path)?;
+    Ok(())
+}
+
+fn open_lock_file() -> Result<File, Box<dyn Error>> {
+    let lock_file_path = system_info::get_wrum_config_path().join(CACHE_LOCK_FILE_NAME);
+    Ok(File::options().write(true).create(true).truncate(false).open(lock_file_path)?)
+}
+
+fn load_entries() -> Result<HashMap<String, CacheEntry>, Box<dyn Error>> {
+    let cache_file_path = get_cache_file_path();
+    if !cache_file_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let contents = fs::read_to_string(&cache_file_path)?;
+    match serde_json::from_str(&contents) {
+        Ok(entries) => Ok(entries),
+        Err(err) => {
+            progress::warn(format!("ignoring invalid release cache {}: {}", cache_file_path.display(), err));
+            Ok(HashMap::new())
+        }
+    }
+}
+
+fn get_cache_file_path() -> PathBuf {
+    system_info::get_wrum_config_path().join(CACHE_FILE_NAME)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use std::thread;
+
+    #[test]
+    fn concurrent_writes_keep_every_entry() {
+        let _config = test_support::set_test_config(Config::default());
+        thread::scope(|scope| {
+            for index in 0..8 {
+                scope.spawn(move || write(&format!("concurrent:{}", index), Value::from(index), 3600).unwrap());
+            }
+        });
+
+        for index in 0..8 {
+            assert_eq!(read(&format!("concurrent:{}", index), None).unwrap(), Some(Value::from(index)));
+        }
+        assert!(!get_cache_file_path().with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn expired_entries_are_ignored_unless_offline() {
+        let _config = test_support::set_test_config(Config::default());
+        let mut entries = load_entries().unwrap();
+        entries.insert(String::from("expired"), CacheEntry { fetched_at: now() - 7200, data: Value::from("old") });
+        fs::write(get_cache_file_path(), serde_json::to_string(&entries).unwrap()).unwrap();
+
+        assert_eq!(read("expired", Some(3600)).unwrap(), None);
+        assert_eq!(read("expired", None).unwrap(), Some(Value::from("old")));
+    }
+
+    #[test]
+    fn huge_ttls_never_expire() {
+        let _config = test_support::set_test_config(Config::default());
+        write("huge-ttl", Value::from("kept"), u64::MAX).unwrap();
+
+        assert_eq!(read("huge-ttl", Some(u64::MAX)).unwrap(), Some(Value::from("kept")));
+    }
+
+    #[test]
+    fn writes_drop_entries_past_the_offline_retention() {
+        let _config = test_support::set_test_config(Config::default());
+        let mut entries = load_entries().unwrap();
+        entries.insert(String::from("stale"), CacheEntry { fetched_at: now() - 3600 - OFFLINE_RETENTION - 1, data: Value::from("stale") });
+        entries.insert(String::from("offline"), CacheEntry { fetched_at: now() - 7200, data: Value::from("offline") });
+        fs::write(get_cache_file_path(), serde_json::to_string(&entries).unwrap()).unwrap();
+
+        write("fresh", Value::from("fresh"), 3600).unwrap();
+        let entries = load_entries().unwrap();
+        assert!(!entries.contains_key("stale"));
+        assert!(entries.contains_key("offline"));
+        assert!(entries.contains_key("fresh"));
+    }
+}
@@ -0,0 +1,72 @@
+use reqwest::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+static NOT_FOUND_ERROR_TYPE: &str = "NotFoundError";
+static NOT_FOUND_ERROR_CODE: &str = "NOT_FOUND";
+
+#[derive(Debug)]
+pub enum ApiError {
+    Transport(reqwest::Error),
+    Http(StatusCode),
+    InvalidResponse(String),
+    NotFound { message: String, error_code: Option<String> },
+    Server { message: String, error_code: Option<String> },
+}
+
+impl ApiError {
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ApiError::Transport(error) => error.is_timeout() || error.is_connect() || error.is_request(),
+            ApiError::Http(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
+
+    pub fn error_code(&self) -> Option<&str> {
+        match self {
+            ApiError::NotFound { error_code, .. } | ApiError::Server { error_code, .. } => error_code.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<graphql_client::Error> for ApiError {
+    fn from(error: graphql_client::Error) -> Self {
+        let extensions = error.extensions.unwrap_or_default();
+        let get_extension = |key: &str| extensions.get(key).and_then(|value| value.as_str()).map(String::from);
+        let error_code = get_extension("errorCode").or(get_extension("code"));
+        let is_not_found = get_extension("__typename").is_some_and(|type_name| type_name == NOT_FOUND_ERROR_TYPE)
+            || error_code.as_deref().is_some_and(|code| code.to_uppercase().contains(NOT_FOUND_ERROR_CODE));
+        match is_not_found {
+            true => ApiError::NotFound { message: error.message, error_code },
+            false => ApiError::Server { message: error.message, error_code },
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Transport(error) => write!(f, "Couldn't reach the Live Platform API: {}", error),
+            ApiError::Http(status) => write!(f, "Live Platform API responded with {}", status),
+            ApiError::InvalidResponse(message) => write!(f, "Live Platform API returned an invalid response: {}", message),
+            ApiError::NotFound { message, error_code } | ApiError::Server { message, error_code } => match error_code {
+                Some(error_code) => write!(f, "Live Platform API error {}: {}", error_code, message),
+                None => write!(f, "Live Platform API error: {}", message),
+            },
+        }
+    }
+}
+
+impl Error for ApiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ApiError::Transport(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -1,10 +1,21 @@
+use crate::progress::ProgressEvent;
+use crate::{config, network, progress};
 use crate::editors::info::SystemArch;
+use crate::live_api::error::ApiError;
 use crate::modules::info::SizeUnitType;
-use ::reqwest::blocking::Client;
-use graphql_client::{reqwest::post_graphql_blocking as post_graphql, GraphQLQuery};
+use crate::version::UnityVersion;
+use ::reqwest::blocking::RequestBuilder;
+use ::reqwest::Method;
+use graphql_client::{GraphQLQuery, Response};
+use serde::Serialize;
 use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-static API_URL: &str = "https://live-platform-api.prd.ld.unity3d.com/graphql";
+mod cache;
+pub mod error;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -14,6 +25,14 @@ static API_URL: &str = "https://live-platform-api.prd.ld.unity3d.com/graphql";
 )]
 pub struct LatestMajorReleases;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/live_api/graphql/schema.graphql",
+    query_path = "src/live_api/graphql/major_version_list.graphql",
+    response_derives = "Debug,Serialize,Clone"
+)]
+pub struct MajorVersionList;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/live_api/graphql/schema.graphql",
@@ -22,7 +41,39 @@ pub struct LatestMajorReleases;
 )]
 pub struct ReleaseInfo;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/live_api/graphql/schema.graphql",
+    query_path = "src/live_api/graphql/release_list.graphql",
+    response_derives = "Debug,Serialize,Clone"
+)]
+pub struct ReleaseList;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/live_api/graphql/schema.graphql",
+    query_path = "src/live_api/graphql/templates.graphql",
+    response_derives = "Debug,Serialize,Clone"
+)]
+pub struct Templates;
+
+const RETRY_BASE_DELAY_MS: u64 = 500;
+const RETRY_MAX_DELAY_MS: u64 = 30000;
+const RELEASE_LIST_PAGE_SIZE: i64 = 25;
+const TEMPLATES_PAGE_SIZE: i64 = 25;
+
+pub struct ReleaseFilter {
+    pub stream: Vec<release_list::UnityReleaseStream>,
+    pub platform: Vec<release_list::UnityReleaseDownloadPlatform>,
+    pub architecture: Vec<SystemArch>,
+    pub entitlements: Vec<release_list::UnityReleaseEntitlement>,
+    pub version: Option<String>,
+    pub order: release_list::UnityReleaseOrder,
+    pub limit: Option<usize>,
+}
+
 type DateTime = String;
+#[allow(clippy::upper_case_acronyms)]
 type URL = String;
 type SubresourceIntegrity = String;
 
@@ -65,32 +116,104 @@ impl From<release_info::UnityReleaseDownloadArchitecture> for SystemArch {
     }
 }
 
+impl From<SystemArch> for release_list::UnityReleaseDownloadArchitecture {
+    fn from(arch: SystemArch) -> release_list::UnityReleaseDownloadArchitecture {
+        match arch {
+            SystemArch::X86_64 => release_list::UnityReleaseDownloadArchitecture::X86_64,
+            SystemArch::ARM64 => release_list::UnityReleaseDownloadArchitecture::ARM64,
+        }
+    }
+}
+
+impl From<String> for release_list::UnityReleaseStream {
+    fn from(value: String) -> Self {
+        match value.to_lowercase().as_str() {
+            "lts" => release_list::UnityReleaseStream::LTS,
+            "beta" => release_list::UnityReleaseStream::BETA,
+            "alpha" => release_list::UnityReleaseStream::ALPHA,
+            "tech" => release_list::UnityReleaseStream::TECH,
+            _ => release_list::UnityReleaseStream::Other(value.to_uppercase()),
+        }
+    }
+}
+
+impl From<release_list::UnityReleaseStream> for String {
+    fn from(value: release_list::UnityReleaseStream) -> Self {
+        match value {
+            release_list::UnityReleaseStream::LTS => String::from("LTS"),
+            release_list::UnityReleaseStream::BETA => String::from("Beta"),
+            release_list::UnityReleaseStream::ALPHA => String::from("Alpha"),
+            release_list::UnityReleaseStream::TECH => String::from("Tech"),
+            release_list::UnityReleaseStream::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for release_list::UnityReleaseDownloadPlatform {
+    fn from(value: String) -> Self {
+        match value.to_lowercase().as_str() {
+            "linux" => release_list::UnityReleaseDownloadPlatform::LINUX,
+            "macos" | "mac_os" => release_list::UnityReleaseDownloadPlatform::MAC_OS,
+            "windows" => release_list::UnityReleaseDownloadPlatform::WINDOWS,
+            _ => release_list::UnityReleaseDownloadPlatform::Other(value.to_uppercase()),
+        }
+    }
+}
+
+impl From<release_info::UnityReleaseDownloadPlatform> for release_list::UnityReleaseDownloadPlatform {
+    fn from(value: release_info::UnityReleaseDownloadPlatform) -> Self {
+        match value {
+            release_info::UnityReleaseDownloadPlatform::LINUX => release_list::UnityReleaseDownloadPlatform::LINUX,
+            release_info::UnityReleaseDownloadPlatform::MAC_OS => release_list::UnityReleaseDownloadPlatform::MAC_OS,
+            release_info::UnityReleaseDownloadPlatform::WINDOWS => release_list::UnityReleaseDownloadPlatform::WINDOWS,
+            release_info::UnityReleaseDownloadPlatform::Other(value) => release_list::UnityReleaseDownloadPlatform::Other(value),
+        }
+    }
+}
+
+impl From<String> for release_list::UnityReleaseEntitlement {
+    fn from(value: String) -> Self {
+        match value.to_lowercase().as_str() {
+            "xlts" => release_list::UnityReleaseEntitlement::XLTS,
+            _ => release_list::UnityReleaseEntitlement::Other(value.to_uppercase()),
+        }
+    }
+}
+
 pub fn get_major_release_list() -> Result<Vec<latest_major_releases::LatestMajorReleasesGetUnityReleaseMajorVersions>, Box<dyn Error>> {
     let variables = latest_major_releases::Variables {
         platform: Some(vec![latest_major_releases::UnityReleaseDownloadPlatform::MAC_OS]),
     };
-    let client = Client::new();
-    let response_body = post_graphql::<LatestMajorReleases, _>(&client, API_URL, variables)?;
+    let response_body = post_graphql::<LatestMajorReleases>(variables)?;
     if let Some(response) = response_body.data {
         return Ok(response.get_unity_release_major_versions);
     }
     Err("Couldn't retrieve major release list".into())
 }
 
+/// Lists the version of every major release line of every stream, such as 2022.3 or 6000.0.
+pub fn get_major_versions() -> Result<Vec<String>, Box<dyn Error>> {
+    let response_body = post_graphql::<MajorVersionList>(major_version_list::Variables {})?;
+    let response = response_body.data.ok_or("Couldn't retrieve major version list")?;
+    Ok(response.get_unity_release_major_versions.into_iter().map(|major| major.version).collect())
+}
+
 pub fn get_version_info(
-    version: &str,
+    version: &UnityVersion,
     platform: release_info::UnityReleaseDownloadPlatform,
     arch: Vec<SystemArch>,
 ) -> Result<Option<release_info::Release>, Box<dyn Error>> {
     let arch = arch.into_iter().map(Into::into).collect();
     let variables = release_info::Variables {
-        version: Some(String::from(version)),
+        version: Some(version.to_string()),
         limit: 1,
         platform: Some(vec![platform]),
         architecture: Some(arch),
     };
-    let client = Client::new();
-    let response_body = post_graphql::<ReleaseInfo, _>(&client, API_URL, variables)?;
+    let response_body = match post_graphql::<ReleaseInfo>(variables) {
+        Err(err) if err.downcast_ref::<ApiError>().is_some_and(|err| matches!(err, ApiError::NotFound { .. })) => return Ok(None),
+        result => result?,
+    };
     if let Some(response) = response_body.data {
         return match response.get_unity_releases.edges.first() {
             None => Ok(None),
@@ -99,3 +222,363 @@ pub fn get_version_info(
     }
     Err("Couldn't retrieve version info".into())
 }
+
+pub fn get_release_list(filter: &ReleaseFilter) -> Result<Vec<release_list::ReleaseListItem>, Box<dyn Error>> {
+    let mut releases = Vec::new();
+    let mut skip = 0;
+    loop {
+        let mut limit = RELEASE_LIST_PAGE_SIZE;
+        if let Some(max_count) = filter.limit {
+            // The API rejects a limit of 0.
+            let remaining = max_count.saturating_sub(releases.len());
+            if remaining == 0 {
+                break;
+            }
+            limit = std::cmp::min(limit, remaining as i64);
+        }
+        let variables = release_list::Variables {
+            skip,
+            limit,
+            order_by: Some(filter.order.clone()),
+            stream: non_empty(filter.stream.clone()),
+            platform: non_empty(filter.platform.clone()),
+            architecture: non_empty(filter.architecture.iter().cloned().map(Into::into).collect()),
+            entitlements: non_empty(filter.entitlements.clone()),
+            version: filter.version.clone(),
+        };
+        let response_body = post_graphql::<ReleaseList>(variables)?;
+        let response = response_body.data.ok_or("Couldn't retrieve release list")?;
+        let page = response.get_unity_releases;
+        let page_size = page.edges.len();
+        releases.extend(page.edges.into_iter().map(|edge| edge.node.release_list_item));
+        skip += page_size as i64;
+        if !page.page_info.has_next_page || page_size == 0 {
+            break;
+        }
+    }
+    Ok(releases)
+}
+
+pub fn get_templates(supported_editor_versions: Vec<String>) -> Result<Vec<templates::TemplateItem>, Box<dyn Error>> {
+    let mut items = Vec::new();
+    let mut skip = 0;
+    loop {
+        let variables = templates::Variables {
+            skip,
+            limit: TEMPLATES_PAGE_SIZE,
+            supported_unity_editor_versions: Some(supported_editor_versions.clone()),
+        };
+        let response_body = post_graphql::<Templates>(variables)?;
+        let response = response_body.data.ok_or("Couldn't retrieve template list")?;
+        let page = response.get_templates;
+        let page_size = page.edges.len();
+        items.extend(page.edges.into_iter().map(|edge| edge.node.template_item));
+        skip += page_size as i64;
+        if !page.page_info.has_next_page || page_size == 0 {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+// An empty list matches nothing on the API side, while an omitted filter matches everything.
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    match values.is_empty() {
+        true => None,
+        false => Some(values),
+    }
+}
+
+fn post_graphql<Q: GraphQLQuery>(variables: Q::Variables) -> Result<Response<Q::ResponseData>, Box<dyn Error>>
+where
+    Q::ResponseData: Serialize,
+{
+    let config = config::get_config()?;
+    if let Some(fixtures_path) = config.api_fixtures_path {
+        return read_fixture::<Q>(&fixtures_path, variables);
+    }
+
+    let query = Q::build_query(variables);
+    let cache_key = format!("{}:{}", query.operation_name, serde_json::to_string(&query.variables)?);
+    if !config.refresh {
+        let ttl = if config.offline { None } else { Some(config.cache_ttl) };
+        if let Some(data) = cache::read(&cache_key, ttl)? {
+            return Ok(Response {
+                data: Some(serde_json::from_value(data)?),
+                errors: None,
+                extensions: None,
+            });
+        }
+    }
+    if config.offline {
+        return Err(format!("{} is not available in the offline release cache", query.operation_name).into());
+    }
+
+    let client = network::get_client_builder()?.timeout(Duration::from_secs(config.api_timeout)).build()?;
+    let request = network::request(&client, Method::POST, &config.api_url)?.json(&query);
+    let mut attempt = 0;
+    let response = loop {
+        match send_query::<Q>(request.try_clone().ok_or("Couldn't build the API request")?) {
+            Err(err) if err.is_transient() && attempt < config.api_retries => {
+                let delay = get_retry_delay(attempt);
+                progress::report(ProgressEvent::RequestRetrying { error: err.to_string(), delay });
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            result => break result?,
+        }
+    };
+    if let Some(data) = &response.data {
+        cache::write(&cache_key, serde_json::to_value(data)?, config.cache_ttl)?;
+    }
+    Ok(response)
+}
+
+fn send_query<Q: GraphQLQuery>(request: RequestBuilder) -> Result<Response<Q::ResponseData>, ApiError> {
+    let response = request.send().map_err(ApiError::Transport)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ApiError::Http(status));
+    }
+    let mut response: Response<Q::ResponseData> = response.json().map_err(|err| ApiError::InvalidResponse(err.to_string()))?;
+    if let Some(error) = response.errors.take().into_iter().flatten().next() {
+        return Err(ApiError::from(error));
+    }
+    Ok(response)
+}
+
+fn get_retry_delay(attempt: u32) -> Duration {
+    let max_delay = std::cmp::min(RETRY_BASE_DELAY_MS.saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX)), RETRY_MAX_DELAY_MS);
+    let jitter_seed = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|duration| duration.subsec_nanos()).unwrap_or(0);
+    Duration::from_millis(max_delay / 2 + jitter_seed as u64 % (max_delay / 2 + 1))
+}
+
+/// Answers a query from `<fixtures>/<OperationName>/<version>.json` when the query has a `version`
+/// variable, falling back to `<fixtures>/<OperationName>.json`. Both hold a raw GraphQL response body.
+fn read_fixture<Q: GraphQLQuery>(fixtures_path: &Path, variables: Q::Variables) -> Result<Response<Q::ResponseData>, Box<dyn Error>> {
+    let query = Q::build_query(variables);
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(version) = serde_json::to_value(&query.variables)?.get("version").and_then(|value| value.as_str()) {
+        candidates.push(fixtures_path.join(query.operation_name).join(format!("{}.json", version)));
+    }
+    candidates.push(fixtures_path.join(format!("{}.json", query.operation_name)));
+    for fixture_path in candidates {
+        if !fixture_path.exists() {
+            continue;
+        }
+        let contents = fs::read_to_string(&fixture_path)?;
+        return serde_json::from_str(&contents).map_err(|err| format!("Couldn't read fixture {}: {}", fixture_path.display(), err).into());
+    }
+    Err(format!("Couldn't find {} fixture in {}", query.operation_name, fixtures_path.display()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn release_info_fixture(version: &str) -> String {
+        serde_json::json!({
+            "data": {
+                "getUnityReleases": {
+                    "edges": [{
+                        "node": {
+                            "__typename": "UnityRelease",
+                            "version": version,
+                            "productName": "UNITY",
+                            "releaseDate": "2024-01-01T00:00:00.000Z",
+                            "releaseNotes": { "url": "https://example.com/notes.md", "integrity": null, "type": "MD" },
+                            "stream": "LTS",
+                            "downloads": [],
+                            "skuFamily": "CLASSIC",
+                            "recommended": true,
+                            "unityHubDeepLink": format!("unityhub://{}/abcdef123456", version),
+                            "shortRevision": "abcdef123456",
+                            "thirdPartyNotices": []
+                        }
+                    }]
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn set_fixtures_config(fixtures_path: &Path) -> std::sync::MutexGuard<'static, ()> {
+        test_support::set_test_config(Config {
+            api_fixtures_path: Some(fixtures_path.to_path_buf()),
+            api_url: String::from("http://127.0.0.1:1/unreachable"),
+            ..Config::default()
+        })
+    }
+
+    #[test]
+    fn fixture_backend_answers_major_release_list() {
+        let fixtures = TempDir::new().unwrap();
+        let response = r#"{"data":{"getUnityReleaseMajorVersions":[{"__typename":"UnityReleaseMajorVersion","version":"2022.3","latestUnityRelease":{"version":"2022.3.10f1","downloads":[{"__typename":"UnityReleaseHubDownload","architecture":"X86_64"}]}}]}}"#;
+        fs::write(fixtures.path().join("LatestMajorReleases.json"), response).unwrap();
+        let _config = set_fixtures_config(fixtures.path());
+
+        let majors = get_major_release_list().unwrap();
+        assert_eq!(majors.len(), 1);
+        assert_eq!(majors[0].item.version, "2022.3");
+    }
+
+    #[test]
+    fn fixture_backend_prefers_version_specific_fixture() {
+        let fixtures = TempDir::new().unwrap();
+        fs::create_dir_all(fixtures.path().join("ReleaseInfo")).unwrap();
+        fs::write(fixtures.path().join("ReleaseInfo").join("2022.3.10f1.json"), release_info_fixture("2022.3.10f1")).unwrap();
+        fs::write(fixtures.path().join("ReleaseInfo.json"), release_info_fixture("6000.0.1f1")).unwrap();
+        let _config = set_fixtures_config(fixtures.path());
+
+        let specific = get_version_info(&UnityVersion::from_str("2022.3.10f1").unwrap(), release_info::UnityReleaseDownloadPlatform::LINUX, vec![SystemArch::X86_64]).unwrap();
+        assert_eq!(specific.unwrap().version, "2022.3.10f1");
+        let fallback = get_version_info(&UnityVersion::from_str("2021.3.1f1").unwrap(), release_info::UnityReleaseDownloadPlatform::LINUX, vec![SystemArch::X86_64]).unwrap();
+        assert_eq!(fallback.unwrap().version, "6000.0.1f1");
+    }
+
+    #[test]
+    fn fixture_backend_fails_without_fixture() {
+        let fixtures = TempDir::new().unwrap();
+        let _config = set_fixtures_config(fixtures.path());
+
+        let err = get_major_release_list().unwrap_err();
+        assert!(err.to_string().contains("Couldn't find LatestMajorReleases fixture"), "{}", err);
+    }
+
+    fn set_server_config(server: &TestServer) -> std::sync::MutexGuard<'static, ()> {
+        test_support::set_test_config(Config {
+            api_url: server.url("/graphql"),
+            refresh: true,
+            ..Config::default()
+        })
+    }
+
+    const MAJOR_VERSIONS_RESPONSE: &str = r#"{"data":{"getUnityReleaseMajorVersions":[{"version":"2022.3"}]}}"#;
+
+    fn start_counting_server(respond: impl Fn(usize) -> TestResponse + Send + Sync + 'static) -> (TestServer, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let server_attempts = attempts.clone();
+        let server = TestServer::start(move |_| respond(server_attempts.fetch_add(1, Ordering::SeqCst)));
+        (server, attempts)
+    }
+
+    fn set_retry_config(server: &TestServer, api_retries: u32) -> std::sync::MutexGuard<'static, ()> {
+        test_support::set_test_config(Config {
+            api_url: server.url("/graphql"),
+            api_retries,
+            api_timeout: 1,
+            refresh: true,
+            ..Config::default()
+        })
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let (server, attempts) = start_counting_server(|attempt| match attempt {
+            0 | 1 => TestResponse::new(503, "unavailable"),
+            _ => TestResponse::new(200, MAJOR_VERSIONS_RESPONSE),
+        });
+        let _config = set_retry_config(&server, 2);
+
+        assert_eq!(get_major_versions().unwrap(), ["2022.3"]);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn gives_up_after_the_configured_retries() {
+        let (server, attempts) = start_counting_server(|_| TestResponse::new(500, "broken"));
+        let _config = set_retry_config(&server, 1);
+
+        assert!(get_major_versions().is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (server, attempts) = start_counting_server(|_| TestResponse::new(400, "bad request"));
+        let _config = set_retry_config(&server, 3);
+
+        assert!(get_major_versions().is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn retries_timeouts() {
+        let (server, attempts) = start_counting_server(|attempt| match attempt {
+            0 => TestResponse::new(200, MAJOR_VERSIONS_RESPONSE).delayed(Duration::from_millis(1500)),
+            _ => TestResponse::new(200, MAJOR_VERSIONS_RESPONSE),
+        });
+        let _config = set_retry_config(&server, 1);
+
+        assert_eq!(get_major_versions().unwrap(), ["2022.3"]);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn retry_delay_is_capped_for_large_attempts() {
+        for attempt in [0, 10, 63, 64, 1000, u32::MAX] {
+            assert!(get_retry_delay(attempt) <= Duration::from_millis(RETRY_MAX_DELAY_MS));
+        }
+    }
+
+    #[test]
+    fn release_list_omits_empty_filters() {
+        let response = r#"{"data":{"getUnityReleases":{"totalCount":0,"pageInfo":{"hasNextPage":false},"edges":[]}}}"#;
+        let server = TestServer::start(move |_| TestResponse::new(200, response));
+        let _config = set_server_config(&server);
+        let filter = ReleaseFilter {
+            stream: Vec::new(),
+            platform: vec![release_list::UnityReleaseDownloadPlatform::LINUX],
+            architecture: Vec::new(),
+            entitlements: Vec::new(),
+            version: None,
+            order: release_list::UnityReleaseOrder::RELEASE_DATE_DESC,
+            limit: Some(10),
+        };
+
+        assert!(get_release_list(&filter).unwrap().is_empty());
+        let requests = server.take_requests();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let variables = &body["variables"];
+        assert!(variables["stream"].is_null());
+        assert!(variables["architecture"].is_null());
+        assert!(variables["entitlements"].is_null());
+        assert_eq!(variables["platform"], serde_json::json!(["LINUX"]));
+    }
+
+    #[test]
+    fn release_list_stops_at_the_requested_count() {
+        let server = TestServer::start(|request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let limit = body["variables"]["limit"].as_u64().unwrap();
+            let edges: Vec<serde_json::Value> = (0..limit).map(|_| serde_json::json!({ "node": {
+                "__typename": "UnityRelease", "version": "2022.3.10f1", "releaseDate": "2024-01-01T00:00:00.000Z", "stream": "LTS",
+                "recommended": false, "shortRevision": "abcdef123456", "entitlements": [], "releaseNotes": { "url": "https://example.com/notes.md", "integrity": null, "type": "MD" }, "downloads": []
+            } })).collect();
+            TestResponse::new(200, serde_json::json!({ "data": { "getUnityReleases": { "totalCount": 1000, "pageInfo": { "hasNextPage": true }, "edges": edges } } }).to_string())
+        });
+        let _config = set_server_config(&server);
+        let get_filter = |limit| ReleaseFilter {
+            stream: Vec::new(),
+            platform: Vec::new(),
+            architecture: Vec::new(),
+            entitlements: Vec::new(),
+            version: None,
+            order: release_list::UnityReleaseOrder::RELEASE_DATE_DESC,
+            limit,
+        };
+
+        assert!(get_release_list(&get_filter(Some(0))).unwrap().is_empty());
+        assert!(server.take_requests().is_empty());
+        assert_eq!(get_release_list(&get_filter(Some(30))).unwrap().len(), 30);
+        let limits: Vec<u64> = server.take_requests().iter().map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap()["variables"]["limit"].as_u64().unwrap()).collect();
+        assert_eq!(limits, [25, 5]);
+    }
+}
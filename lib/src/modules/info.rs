@@ -24,10 +24,10 @@ impl From<SizeUnitType> for f64 {
 impl From<DownloadSize> for f64 {
     fn from(value: DownloadSize) -> Self {
         match value.unit {
-            ReleaseDigitalUnit::BYTE => value.value as f64,
-            ReleaseDigitalUnit::KILOBYTE => value.value as f64 * 1024.0,
-            ReleaseDigitalUnit::MEGABYTE => value.value as f64 * 1024.0 * 1024.0,
-            ReleaseDigitalUnit::GIGABYTE => value.value as f64 * 1024.0 * 1024.0 * 1024.0,
+            ReleaseDigitalUnit::BYTE => value.value,
+            ReleaseDigitalUnit::KILOBYTE => value.value * 1024.0,
+            ReleaseDigitalUnit::MEGABYTE => value.value * 1024.0 * 1024.0,
+            ReleaseDigitalUnit::GIGABYTE => value.value * 1024.0 * 1024.0 * 1024.0,
             _ => unimplemented!(),
         }
     }
@@ -115,7 +115,7 @@ impl From<ModuleDownload> for ModuleInfo {
             info.extracted_path_rename = Some(extracted_rename.path_rename);
         }
         if let Some(eula) = module_download.eula {
-            if eula.len() > 0 {
+            if !eula.is_empty() {
                 let eula = eula.first().unwrap();
                 info.eula_url_1 = eula.url.clone();
                 info.eula_label_1 = eula.label.clone();
@@ -0,0 +1,110 @@
+use crate::install::integrity;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+static MANIFESTS_FOLDER_NAME: &str = "module_files";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ManifestFile {
+    pub path: PathBuf,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+}
+
+impl ManifestFile {
+    /// Resolves the recorded path inside the editor folder, refusing paths that would point outside of it.
+    pub fn get_path(&self, editor_path: impl AsRef<Path>) -> Result<PathBuf, Box<dyn Error>> {
+        if !self.path.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(format!("{} is outside of the editor folder", self.path.display()).into());
+        }
+        Ok(editor_path.as_ref().join(&self.path))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ModuleManifest {
+    pub id: String,
+    pub files: Vec<ManifestFile>,
+}
+
+impl ModuleManifest {
+    /// Only the files inside the editor folder are tracked, so removing a module never touches anything else.
+    pub fn new(id: &str, editor_path: impl AsRef<Path>, files: Vec<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let mut manifest_files = Vec::new();
+        for file in files {
+            let path = match file.strip_prefix(editor_path.as_ref()) {
+                Ok(relative_path) => relative_path.to_path_buf(),
+                Err(_) => continue,
+            };
+            let metadata = fs::symlink_metadata(&file)?;
+            let integrity = match metadata.is_symlink() {
+                true => None,
+                false => Some(integrity::get_file_integrity(&file)?),
+            };
+            manifest_files.push(ManifestFile { path, size: metadata.len(), integrity });
+        }
+        Ok(ModuleManifest { id: String::from(id), files: manifest_files })
+    }
+}
+
+pub fn read_manifest(editor_path: impl AsRef<Path>, id: &str) -> Result<Option<ModuleManifest>, Box<dyn Error>> {
+    let manifest_path = get_manifest_path(editor_path, id);
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(manifest_path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+pub fn write_manifest(editor_path: impl AsRef<Path>, manifest: &ModuleManifest) -> Result<(), Box<dyn Error>> {
+    let manifest_path = get_manifest_path(&editor_path, &manifest.id);
+    fs::create_dir_all(editor_path.as_ref().join(MANIFESTS_FOLDER_NAME))?;
+    if fs::write(manifest_path, serde_json::to_string(manifest)?).is_err() {
+        return Err(format!("Couldn't write the file manifest of {}", manifest.id).into());
+    }
+    Ok(())
+}
+
+pub fn remove_manifest(editor_path: impl AsRef<Path>, id: &str) -> Result<(), Box<dyn Error>> {
+    let manifest_path = get_manifest_path(editor_path, id);
+    if manifest_path.exists() {
+        fs::remove_file(manifest_path)?;
+    }
+    Ok(())
+}
+
+fn get_manifest_path(editor_path: impl AsRef<Path>, id: &str) -> PathBuf {
+    editor_path.as_ref().join(MANIFESTS_FOLDER_NAME).join(format!("{}.json", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn only_files_inside_the_editor_are_recorded() {
+        let editor = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(editor.path().join("inside.txt"), "inside").unwrap();
+        fs::write(outside.path().join("outside.txt"), "outside").unwrap();
+
+        let manifest = ModuleManifest::new("module", editor.path(), vec![editor.path().join("inside.txt"), outside.path().join("outside.txt")]).unwrap();
+        let paths: Vec<&Path> = manifest.files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("inside.txt")]);
+    }
+
+    #[test]
+    fn paths_outside_the_editor_are_refused() {
+        let editor = TempDir::new().unwrap();
+        let file = |path: &str| ManifestFile { path: PathBuf::from(path), size: 0, integrity: None };
+
+        assert_eq!(file("Data/file.txt").get_path(editor.path()).unwrap(), editor.path().join("Data/file.txt"));
+        assert!(file("../file.txt").get_path(editor.path()).is_err());
+        assert!(file("Data/../../file.txt").get_path(editor.path()).is_err());
+        assert!(file(&editor.path().join("file.txt").to_string_lossy()).get_path(editor.path()).is_err());
+    }
+}
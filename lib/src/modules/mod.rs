@@ -1,19 +1,23 @@
 pub mod info;
+pub mod manifest;
 
 use crate::modules::info::ModuleInfo;
-use crate::{editors, install, live_api, system_info};
+use crate::modules::manifest::ModuleManifest;
+use crate::progress::ProgressEvent;
+use crate::version::UnityVersion;
+use crate::{editors, install, live_api, progress, system_info};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn install_modules(version: &str, modules: Vec<String>, arch: Option<String>, include_children: bool) -> Result<(), Box<dyn Error>> {
+pub fn install_modules(version: &UnityVersion, modules: Vec<String>, arch: Option<String>, include_children: bool) -> Result<(), Box<dyn Error>> {
     let editor_path = editors::get_installed_editor_path(version, arch)?;
     if let Some(editor_path) = editor_path {
         let mut modules_to_install = vec![];
         let mut editor_modules_info = read_modules_info(&editor_path)?;
         for module_in_args in modules {
-            push_module_to_install(module_in_args, &editor_modules_info, &mut modules_to_install, include_children);
+            push_module_to_install(module_in_args, &editor_modules_info, &mut modules_to_install, include_children)?;
         }
 
         if modules_to_install.is_empty() {
@@ -35,15 +39,12 @@ pub fn install_modules(version: &str, modules: Vec<String>, arch: Option<String>
         }
 
         for module_to_install in modules_to_install {
-            let destination = module_to_install.destination.unwrap_or(String::from("/Applications"));
-
             let lock_file = install::get_install_lock(&module_to_install.id, &editor_path)?;
             editor_modules_info = read_modules_info(&editor_path)?;
-            if !editor_modules_info[&module_to_install.id].selected {
-                install::install(&module_to_install.url, &module_to_install.id, &editor_path,
-                                 module_to_install.module_type.clone(), &destination,
-                                 &module_to_install.rename_from, &module_to_install.rename_to)?;
-                editor_modules_info.get_mut(&module_to_install.id).unwrap().selected = true;
+            let is_selected = editor_modules_info.get(&module_to_install.id).is_some_and(|module| module.selected);
+            if !is_selected {
+                install_module_files(&editor_path, &module_to_install)?;
+                editor_modules_info.entry(module_to_install.id.clone()).or_insert(module_to_install).selected = true;
                 write_modules_info(&editor_path, editor_modules_info.values().cloned().collect())?;
             }
             install::release_install_lock(lock_file)?;
@@ -54,32 +55,159 @@ pub fn install_modules(version: &str, modules: Vec<String>, arch: Option<String>
     Err("Editor not found".into())
 }
 
-fn push_module_to_install(module: String, modules_info: &HashMap<String, ModuleInfo>, modules_to_install: &mut Vec<ModuleInfo>, include_children: bool) {
-    if !modules_info.contains_key(&module) {
+pub fn install_module_from_archive(version: &UnityVersion, module: &str, archive_path: &Path, arch: Option<String>) -> Result<(), Box<dyn Error>> {
+    if !archive_path.is_file() {
+        return Err(format!("{} does not exist", archive_path.display()).into());
+    }
+    let editor_path = editors::get_installed_editor_path(version, arch)?.ok_or("Editor not found")?;
+    let module_id = module.to_lowercase();
+    let lock_file = install::get_install_lock(&module_id, &editor_path)?;
+    // Read from disk only: the list is saved back, so a list rebuilt from the API or left empty would drop the other modules.
+    let mut editor_modules_info: HashMap<String, ModuleInfo> = match editor_path.join("modules.json").exists() {
+        true => load_modules_from_disk(&editor_path)
+            .map_err(|err| format!("Couldn't read the modules.json of {}: {}", editor_path.display(), err))?
+            .into_iter()
+            .map(|module| (module.id.clone(), module))
+            .collect(),
+        false => HashMap::new(),
+    };
+    let module_info = match editor_modules_info.get(&module_id) {
+        Some(module_info) => module_info.clone(),
+        None => editors::archive::get_local_archive_module(&module_id, archive_path),
+    };
+    if fs4::available_space(&editor_path)? < editors::archive::get_local_archive_installed_size(archive_path)? {
+        return Err("Not enough free disk space".into());
+    }
+    install_module_files_from(&editor_path, &module_info, Some(archive_path))?;
+    editor_modules_info.entry(module_id).or_insert(module_info).selected = true;
+    write_modules_info(&editor_path, editor_modules_info.into_values().collect())?;
+    install::release_install_lock(lock_file)
+}
+
+pub(crate) fn install_module_files(editor_path: &Path, module: &ModuleInfo) -> Result<(), Box<dyn Error>> {
+    install_module_files_from(editor_path, module, None)
+}
+
+fn install_module_files_from(editor_path: &Path, module: &ModuleInfo, archive_path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let destination = module.destination.clone().unwrap_or(String::from("/Applications"));
+    let files = match archive_path {
+        None => install::install(&module.url, module.integrity.as_deref(), &module.id, editor_path, module.module_type.clone(), &destination, &module.rename_from, &module.rename_to)?,
+        Some(archive_path) => install::install_file(archive_path, &module.id, editor_path, editors::archive::get_file_type(&archive_path.to_string_lossy()),
+                                                    &destination, &module.rename_from, &module.rename_to)?,
+    };
+    manifest::write_manifest(editor_path, &ModuleManifest::new(&module.id, editor_path, files)?)
+}
+
+pub fn uninstall_modules(version: &UnityVersion, modules: Vec<String>, arch: Option<String>) -> Result<Vec<String>, Box<dyn Error>> {
+    let editor_path = editors::get_installed_editor_path(version, arch)?.ok_or("Editor not found")?;
+    let editor_modules_info = read_modules_info(&editor_path)?;
+    let mut modules_to_uninstall = vec![];
+    for module_in_args in modules {
+        match editor_modules_info.get(&module_in_args) {
+            None => return Err(format!("Unknown module \"{}\"", module_in_args).into()),
+            Some(module) if !module.selected => return Err(format!("{} is not installed", module_in_args).into()),
+            Some(_) => push_module_to_uninstall(module_in_args, &editor_modules_info, &mut modules_to_uninstall),
+        }
+    }
+
+    let mut manifests = vec![];
+    for module_id in &modules_to_uninstall {
+        let manifest = manifest::read_manifest(&editor_path, module_id)?
+            .ok_or(format!("Couldn't find the installed files of {}. It was installed before wrum tracked module files", module_id))?;
+        // Checked up front so a bad manifest doesn't leave a module half removed.
+        for file in &manifest.files {
+            file.get_path(&editor_path)?;
+        }
+        manifests.push(manifest);
+    }
+
+    for manifest in manifests {
+        let lock_file = install::get_install_lock(&manifest.id, &editor_path)?;
+        progress::report(ProgressEvent::UninstallStarted { id: manifest.id.clone() });
+        for file in &manifest.files {
+            remove_module_file(&editor_path, &file.get_path(&editor_path)?)?;
+        }
+        manifest::remove_manifest(&editor_path, &manifest.id)?;
+        let mut editor_modules_info = read_modules_info(&editor_path)?;
+        if let Some(module) = editor_modules_info.get_mut(&manifest.id) {
+            module.selected = false;
+        }
+        write_modules_info(&editor_path, editor_modules_info.into_values().collect())?;
+        install::release_install_lock(lock_file)?;
+        progress::report(ProgressEvent::UninstallFinished { id: manifest.id });
+    }
+    Ok(modules_to_uninstall)
+}
+
+fn push_module_to_uninstall(module: String, modules_info: &HashMap<String, ModuleInfo>, modules_to_uninstall: &mut Vec<String>) {
+    if modules_to_uninstall.contains(&module) {
         return;
     }
-    for module_in_list in modules_to_install.clone() {
-        if module == module_in_list.id {
-            return;
+    for submodule_info in get_sorted_modules(modules_info) {
+        if submodule_info.parent == module && submodule_info.selected {
+            push_module_to_uninstall(submodule_info.id.clone(), modules_info, modules_to_uninstall);
         }
     }
-    let module = modules_info[&module].clone();
-    let parent_module = module.parent.clone();
-    if !parent_module.is_empty() && !modules_info[&parent_module].selected {
-        push_module_to_install(parent_module, modules_info, modules_to_install, false);
+    modules_to_uninstall.push(module);
+}
+
+pub(crate) fn remove_module_file(editor_path: &Path, file_path: &Path) -> Result<(), Box<dyn Error>> {
+    if fs::symlink_metadata(file_path).is_err() {
+        return Ok(());
+    }
+    fs::remove_file(file_path)?;
+    let mut parent = file_path.parent();
+    while let Some(folder) = parent {
+        if folder == editor_path || !folder.starts_with(editor_path) || fs::read_dir(folder)?.next().is_some() {
+            break;
+        }
+        fs::remove_dir(folder)?;
+        parent = folder.parent();
     }
-    if module.selected != true {
+    Ok(())
+}
+
+fn push_module_to_install(module: String, modules_info: &HashMap<String, ModuleInfo>, modules_to_install: &mut Vec<ModuleInfo>, include_children: bool) -> Result<(), Box<dyn Error>> {
+    let module = match modules_info.get(&module) {
+        None => return Ok(()),
+        Some(module) => module.clone(),
+    };
+    let is_listed = |modules_to_install: &Vec<ModuleInfo>| modules_to_install.iter().any(|module_in_list| module_in_list.id == module.id);
+    if is_listed(modules_to_install) {
+        return Ok(());
+    }
+    for required_module in [&module.parent, &module.sync] {
+        if !required_module.is_empty() && !modules_info.contains_key(required_module) {
+            return Err(format!("{} requires the unknown module {}", module.id, required_module).into());
+        }
+    }
+    if !module.parent.is_empty() && !modules_info[&module.parent].selected {
+        push_module_to_install(module.parent.clone(), modules_info, modules_to_install, false)?;
+        // The parent already lists the children synced with it.
+        if is_listed(modules_to_install) {
+            return Ok(());
+        }
+    }
+    if !module.selected {
         modules_to_install.push(module.clone());
     }
 
-    if let Some(submodules) = module.submodules {
-        for submodule in submodules {
-            let submodule_info = modules_info[&submodule.id].clone();
-            if include_children || submodule_info.sync == module.id {
-                push_module_to_install(submodule.id, modules_info, modules_to_install, include_children);
-            }
+    for submodule_info in get_sorted_modules(modules_info) {
+        if submodule_info.parent != module.id {
+            continue;
+        }
+        if include_children || submodule_info.sync == module.id {
+            push_module_to_install(submodule_info.id.clone(), modules_info, modules_to_install, include_children)?;
         }
     }
+    Ok(())
+}
+
+// Walks the modules by id so the install and uninstall order doesn't depend on the map order.
+fn get_sorted_modules(modules_info: &HashMap<String, ModuleInfo>) -> Vec<&ModuleInfo> {
+    let mut modules: Vec<&ModuleInfo> = modules_info.values().collect();
+    modules.sort_by(|a, b| a.id.cmp(&b.id));
+    modules
 }
 
 pub fn read_modules_info(path: impl AsRef<Path>) -> Result<HashMap<String, ModuleInfo>, Box<dyn Error>> {
@@ -90,11 +218,7 @@ pub fn read_modules_info(path: impl AsRef<Path>) -> Result<HashMap<String, Modul
     let modules_vec = match load_modules_from_disk(path.as_ref()) {
         Ok(modules) => modules,
         Err(err) => {
-            eprintln!(
-                "modules.json is missing or invalid for {} ({}). Attempting to refresh metadata...",
-                path.as_ref().display(),
-                err
-            );
+            progress::warn(format!("modules.json is missing or invalid for {} ({}). Attempting to refresh metadata...", path.as_ref().display(), err));
             rebuild_modules_metadata(path.as_ref())?
         }
     };
@@ -110,7 +234,7 @@ pub fn write_modules_info(path: impl AsRef<Path>, modules: Vec<ModuleInfo>) -> R
     Ok(())
 }
 
-fn load_modules_from_disk(path: &Path) -> Result<Vec<ModuleInfo>, Box<dyn Error>> {
+pub(crate) fn load_modules_from_disk(path: &Path) -> Result<Vec<ModuleInfo>, Box<dyn Error>> {
     let modules_path = PathBuf::from(path).join("modules.json");
     let contents = fs::read_to_string(modules_path)?;
     let info: Vec<ModuleInfo> = serde_json::from_str(&contents)?;
@@ -144,3 +268,84 @@ fn rebuild_modules_metadata(path: &Path) -> Result<Vec<ModuleInfo>, Box<dyn Erro
     write_modules_info(path, modules.clone())?;
     Ok(modules)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::editors::archive;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use std::str::FromStr;
+
+    fn module(id: &str, parent: &str, selected: bool) -> ModuleInfo {
+        let mut module = archive::get_local_archive_module(id, Path::new("module.tar.xz"));
+        module.parent = String::from(parent);
+        module.sync = String::from(parent);
+        module.selected = selected;
+        module
+    }
+
+    fn modules_info(modules: Vec<ModuleInfo>) -> HashMap<String, ModuleInfo> {
+        modules.into_iter().map(|module| (module.id.clone(), module)).collect()
+    }
+
+    fn ids(modules: &[ModuleInfo]) -> Vec<&str> {
+        modules.iter().map(|module| module.id.as_str()).collect()
+    }
+
+    #[test]
+    fn synced_children_are_installed_in_id_order() {
+        let modules_info = modules_info(vec![
+            module("android-sdk-platform-tools", "android", false),
+            module("android", "", false),
+            module("android-open-jdk", "android", false),
+            module("android-ndk", "android", false),
+            module("ios", "", false),
+        ]);
+        for _ in 0..5 {
+            let mut modules_to_install = Vec::new();
+            push_module_to_install(String::from("android"), &modules_info, &mut modules_to_install, false).unwrap();
+            assert_eq!(ids(&modules_to_install), ["android", "android-ndk", "android-open-jdk", "android-sdk-platform-tools"]);
+        }
+    }
+
+    #[test]
+    fn missing_parents_are_installed_first() {
+        let modules_info = modules_info(vec![module("android", "", false), module("android-ndk", "android", false), module("ios", "", true)]);
+        let mut modules_to_install = Vec::new();
+        push_module_to_install(String::from("android-ndk"), &modules_info, &mut modules_to_install, false).unwrap();
+        push_module_to_install(String::from("ios"), &modules_info, &mut modules_to_install, false).unwrap();
+        push_module_to_install(String::from("unknown"), &modules_info, &mut modules_to_install, false).unwrap();
+        assert_eq!(ids(&modules_to_install), ["android", "android-ndk"]);
+    }
+
+    #[test]
+    fn unknown_parent_is_an_error() {
+        let modules_info = modules_info(vec![module("android-ndk", "android", false)]);
+        let mut modules_to_install = Vec::new();
+        let err = push_module_to_install(String::from("android-ndk"), &modules_info, &mut modules_to_install, false).unwrap_err();
+        assert_eq!(err.to_string(), "android-ndk requires the unknown module android");
+    }
+
+    #[test]
+    fn archive_modules_keep_an_unreadable_module_list() {
+        let server = TestServer::start(|_| TestResponse::new(500, "unexpected"));
+        let _config = test_support::set_test_config(Config { api_url: server.url("/graphql"), api_retries: 0, ..Config::default() });
+        let version = UnityVersion::from_str("2021.3.36f1").unwrap();
+        let editor_path = system_info::get_default_install_path().join(version.to_string());
+        let executable_path = system_info::get_editor_executable_path(&editor_path);
+        fs::create_dir_all(executable_path.parent().unwrap()).unwrap();
+        fs::write(executable_path, "").unwrap();
+        fs::write(editor_path.join("wrum.json"), r#"{"version":"2021.3.36f1","arch":"X86_64","downloadType":"TAR_XZ"}"#).unwrap();
+        fs::write(editor_path.join("modules.json"), "[{").unwrap();
+        let archive_path = editor_path.join("android.tar.xz");
+        fs::write(&archive_path, "archive").unwrap();
+
+        let err = install_module_from_archive(&version, "android", &archive_path, None).unwrap_err();
+        assert!(err.to_string().contains("Couldn't read the modules.json"), "{}", err);
+        assert_eq!(fs::read_to_string(editor_path.join("modules.json")).unwrap(), "[{");
+        assert!(server.take_requests().is_empty());
+        fs::remove_dir_all(editor_path).unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+use crate::config;
+use crate::config::Config;
+use regex::{Captures, Regex};
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Method, Proxy, Url};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::sync::LazyLock;
+
+const MAX_REDIRECTS: usize = 10;
+
+static ENV_VAR_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+
+/// A url after the rewrite rules, with the headers configured for its host. Resolving it once lets repeated
+/// requests, like the chunks of a download, skip reading the config and expanding the headers again.
+#[derive(Clone)]
+pub struct RequestTarget {
+    url: Url,
+    headers: HeaderMap,
+}
+
+impl RequestTarget {
+    pub fn new(url: &str) -> Result<RequestTarget, Box<dyn Error>> {
+        let config = config::get_config()?;
+        let rewritten_url = Url::parse(&rewrite_url(&config, url)).map_err(|err| format!("Invalid url {}: {}", url, err))?;
+        let headers = get_host_headers(&config, &rewritten_url)?;
+        Ok(RequestTarget { url: rewritten_url, headers })
+    }
+
+    pub fn request(&self, client: &Client, method: Method) -> RequestBuilder {
+        client.request(method, self.url.clone()).headers(self.headers.clone())
+    }
+
+    /// Sends a request and follows the redirects the client stopped at because they leave a host with configured
+    /// headers. Each redirect gets the headers of its own host, so a mirror token never reaches the storage it
+    /// redirects to. build adds the request specific parts, like a range.
+    pub fn send(&self, client: &Client, method: Method, build: impl Fn(RequestBuilder) -> RequestBuilder) -> Result<Response, Box<dyn Error>> {
+        let mut target = self.clone();
+        for _ in 0..MAX_REDIRECTS {
+            let response = build(target.request(client, method.clone())).send()?;
+            let location = match response.headers().get(LOCATION) {
+                Some(location) if response.status().is_redirection() => location.to_str()?,
+                _ => return Ok(response),
+            };
+            let url = response.url().join(location)?;
+            if url.origin() == response.url().origin() {
+                return Ok(response);
+            }
+            let headers = get_host_headers(&config::get_config()?, &url)?;
+            target = RequestTarget { url, headers };
+        }
+        Err(format!("Too many redirects for {}", self.url).into())
+    }
+}
+
+/// Creates a client honoring the configured user agent, proxy and extra CA bundle.
+pub fn get_client_builder() -> Result<ClientBuilder, Box<dyn Error>> {
+    let config = config::get_config()?;
+    // Redirects leaving a host with configured headers are followed by RequestTarget::send instead, since the client
+    // would forward the headers to the new host.
+    let header_hosts: Vec<String> = config.http_headers.keys().cloned().collect();
+    let redirect_policy = Policy::custom(move |attempt| {
+        let original_url = &attempt.previous()[0];
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if attempt.url().origin() != original_url.origin() && header_hosts.iter().any(|host| is_host(host, original_url)) {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    });
+    let mut builder = Client::builder().user_agent(config.user_agent).redirect(redirect_policy);
+    if let Some(proxy) = config.proxy {
+        builder = builder.proxy(Proxy::all(&proxy).map_err(|err| format!("Invalid proxy {}: {}", proxy, err))?);
+    }
+    if let Some(ca_bundle) = config.ca_bundle {
+        let pem_bundle = fs::read(&ca_bundle).map_err(|err| format!("Couldn't read the CA bundle {}: {}", ca_bundle.display(), err))?;
+        for certificate in Certificate::from_pem_bundle(&pem_bundle)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+pub fn get_client() -> Result<Client, Box<dyn Error>> {
+    Ok(get_client_builder()?.build()?)
+}
+
+/// Builds a request to the rewritten url, with the headers configured for its host.
+pub fn request(client: &Client, method: Method, url: &str) -> Result<RequestBuilder, Box<dyn Error>> {
+    Ok(RequestTarget::new(url)?.request(client, method))
+}
+
+pub fn get(url: &str) -> Result<Response, Box<dyn Error>> {
+    RequestTarget::new(url)?.send(&get_client()?, Method::GET, |request| request)
+}
+
+/// Applies the first rewrite rule whose prefix matches the url.
+pub fn rewrite_url(config: &Config, url: &str) -> String {
+    for rule in &config.url_rewrites {
+        if let Some(rest) = url.strip_prefix(&rule.from) {
+            return format!("{}{}", rule.to, rest);
+        }
+    }
+    String::from(url)
+}
+
+fn get_host_headers(config: &Config, url: &Url) -> Result<HeaderMap, Box<dyn Error>> {
+    let mut headers = HeaderMap::new();
+    let host_headers = config.http_headers.iter().filter(|(configured_host, _)| is_host(configured_host, url));
+    for (_, values) in host_headers {
+        for (name, value) in values {
+            let value = expand_env_vars(value)?;
+            headers.insert(HeaderName::from_bytes(name.as_bytes())?, HeaderValue::from_str(&value).map_err(|_| format!("Invalid value for header {}", name))?);
+        }
+    }
+    Ok(headers)
+}
+
+// Headers are configured for a host, or a host and port.
+fn is_host(configured_host: &str, url: &Url) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    configured_host == host || url.port().is_some_and(|port| configured_host == format!("{}:{}", host, port))
+}
+
+// Header values can reference environment variables as ${NAME} so tokens don't have to be stored in the config.
+fn expand_env_vars(value: &str) -> Result<String, Box<dyn Error>> {
+    let mut missing = None;
+    let expanded = ENV_VAR_REGEX.replace_all(value, |captures: &Captures| {
+        env::var(&captures[1]).unwrap_or_else(|_| {
+            missing = Some(captures[1].to_string());
+            String::new()
+        })
+    });
+    match missing {
+        Some(name) => Err(format!("Environment variable {} used in the HTTP headers isn't set", name).into()),
+        None => Ok(expanded.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UrlRewrite;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use std::collections::HashMap;
+
+    fn rewrite(from: &str, to: &str) -> UrlRewrite {
+        UrlRewrite { from: String::from(from), to: String::from(to) }
+    }
+
+    fn get_headers(http_headers: &[(&str, &str, &str)], url: &str) -> Vec<(String, String)> {
+        let mut config = Config::default();
+        for (host, name, value) in http_headers {
+            config.http_headers.entry(String::from(*host)).or_insert_with(HashMap::new).insert(String::from(*name), String::from(*value));
+        }
+        let mut headers: Vec<(String, String)> = get_host_headers(&config, &Url::parse(url).unwrap())
+            .unwrap()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+            .collect();
+        headers.sort();
+        headers
+    }
+
+    #[test]
+    fn rewrite_url_applies_the_first_matching_prefix() {
+        let config = Config {
+            url_rewrites: vec![
+                rewrite("https://download.unity3d.com/download_unity/", "https://mirror.example.com/unity/"),
+                rewrite("https://download.unity3d.com/", "https://other.example.com/"),
+            ],
+            ..Config::default()
+        };
+
+        assert_eq!(rewrite_url(&config, "https://download.unity3d.com/download_unity/abc/Unity.tar.xz"), "https://mirror.example.com/unity/abc/Unity.tar.xz");
+        assert_eq!(rewrite_url(&config, "https://download.unity3d.com/hub/UnityHub.AppImage"), "https://other.example.com/hub/UnityHub.AppImage");
+        assert_eq!(rewrite_url(&config, "https://public-cdn.cloud.unity3d.com/file"), "https://public-cdn.cloud.unity3d.com/file");
+    }
+
+    #[test]
+    fn host_headers_match_the_host_or_the_host_with_its_port() {
+        let http_headers = [("mirror.example.com", "X-Host", "host"), ("mirror.example.com:8443", "X-Port", "port"), ("other.example.com", "X-Other", "other")];
+
+        assert_eq!(get_headers(&http_headers, "https://mirror.example.com/file"), [(String::from("x-host"), String::from("host"))]);
+        assert_eq!(
+            get_headers(&http_headers, "https://mirror.example.com:8443/file"),
+            [(String::from("x-host"), String::from("host")), (String::from("x-port"), String::from("port"))]
+        );
+        assert!(get_headers(&http_headers, "https://mirror.example.com.evil.com:8443/file").is_empty());
+        assert!(get_headers(&http_headers, "https://sub.mirror.example.com/file").is_empty());
+    }
+
+    #[test]
+    fn header_values_expand_environment_variables() {
+        env::set_var("WRUM_TEST_HEADER_TOKEN", "secret");
+
+        assert_eq!(expand_env_vars("Bearer ${WRUM_TEST_HEADER_TOKEN}").unwrap(), "Bearer secret");
+        assert_eq!(expand_env_vars("$WRUM_TEST_HEADER_TOKEN").unwrap(), "$WRUM_TEST_HEADER_TOKEN");
+        let err = expand_env_vars("Bearer ${WRUM_TEST_HEADER_MISSING}").unwrap_err();
+        assert!(err.to_string().contains("WRUM_TEST_HEADER_MISSING"), "{}", err);
+    }
+
+    #[test]
+    fn configured_headers_are_not_forwarded_to_other_hosts() {
+        let storage = TestServer::start(|_| TestResponse::new(200, "archive"));
+        let storage_url = storage.url("/bucket/archive.tar.xz");
+        let mirror = TestServer::start(move |request| match request.path.as_str() {
+            "/moved" => TestResponse::new(302, "").with_header("Location", "/archive.tar.xz"),
+            "/archive.tar.xz" => TestResponse::new(302, "").with_header("Location", &storage_url),
+            _ => TestResponse::new(404, ""),
+        });
+        let mut config = Config::default();
+        config.http_headers.insert(mirror.host(), HashMap::from([(String::from("PRIVATE-TOKEN"), String::from("secret"))]));
+        config.http_headers.insert(storage.host(), HashMap::from([(String::from("X-Storage"), String::from("storage"))]));
+        let _config = test_support::set_test_config(config);
+
+        let response = get(&mirror.url("/moved")).unwrap();
+        assert_eq!(response.url().as_str(), storage.url("/bucket/archive.tar.xz"));
+        assert_eq!(response.text().unwrap(), "archive");
+        let mirror_requests = mirror.take_requests();
+        assert_eq!(mirror_requests.len(), 2);
+        assert!(mirror_requests.iter().all(|request| request.header("private-token") == Some("secret")));
+        let storage_requests = storage.take_requests();
+        assert_eq!(storage_requests[0].header("private-token"), None);
+        assert_eq!(storage_requests[0].header("x-storage"), Some("storage"));
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+static REPORTER: RwLock<Option<Arc<dyn ProgressReporter>>> = RwLock::new(None);
+
+/// Something the library is doing, reported instead of being printed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    WaitingForLock { id: String },
+    AlreadyInstalled { id: String },
+    DownloadStarted { id: String, size: Option<u64> },
+    DownloadProgress { id: String, downloaded: u64, size: u64 },
+    DownloadFinished { id: String },
+    DownloadCached { id: String },
+    VerifyStarted { id: String },
+    IntegrityMismatch { id: String },
+    UnpackStarted { id: String },
+    UnpackFinished { id: String },
+    UninstallStarted { id: String },
+    UninstallFinished { id: String },
+    RepairStarted { id: String },
+    WorkaroundApplied { path: PathBuf },
+    RequestRetrying { error: String, delay: Duration },
+    Warning { message: String },
+}
+
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: &ProgressEvent);
+}
+
+/// Sets the reporter receiving the events of every following operation. Nothing is reported until one is set.
+pub fn set_reporter(reporter: Arc<dyn ProgressReporter>) {
+    *REPORTER.write().unwrap() = Some(reporter);
+}
+
+pub(crate) fn report(event: ProgressEvent) {
+    if let Some(reporter) = REPORTER.read().unwrap().as_ref() {
+        reporter.report(&event);
+    }
+}
+
+pub(crate) fn warn(message: impl Into<String>) {
+    report(ProgressEvent::Warning { message: message.into() });
+}
@@ -0,0 +1,178 @@
+use crate::live_api::release_info::FileType;
+use crate::live_api::release_list::UnityReleaseOrder;
+use crate::live_api::ReleaseFilter;
+use crate::version::UnityVersion;
+use crate::{live_api, network, system_info};
+use regex::Regex;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Serialize)]
+pub struct ReleaseNotes {
+    pub version: UnityVersion,
+    pub text: String,
+}
+
+pub fn search_release_notes<'a>(notes: &'a [ReleaseNotes], pattern: &str) -> Result<Vec<(&'a UnityVersion, &'a str)>, Box<dyn Error>> {
+    let pattern = Regex::new(pattern)?;
+    let mut matches = Vec::new();
+    for release_notes in notes {
+        for line in release_notes.text.lines().filter(|line| pattern.is_match(line)) {
+            matches.push((&release_notes.version, line));
+        }
+    }
+    Ok(matches)
+}
+
+pub fn get_release_notes(version: &UnityVersion) -> Result<ReleaseNotes, Box<dyn Error>> {
+    let release = live_api::get_version_info(version, system_info::get_platform(), system_info::get_supported_editor_arch())?
+        .ok_or(format!("Couldn't find release {}", version))?;
+    let is_markdown = matches!(release.release_notes.type_, FileType::MD);
+    Ok(ReleaseNotes {
+        version: version.clone(),
+        text: download_release_notes(&release.release_notes.url, is_markdown)?,
+    })
+}
+
+pub fn get_release_notes_between(from: &UnityVersion, to: &UnityVersion) -> Result<Vec<ReleaseNotes>, Box<dyn Error>> {
+    let (from, to) = match from <= to {
+        true => (from, to),
+        false => (to, from),
+    };
+    // Each major release line is queried on its own, so releases of unrelated streams and years aren't paged through.
+    let majors = match (from.major(), from.minor()) == (to.major(), to.minor()) {
+        true => vec![format!("{}.{}", from.major(), from.minor())],
+        false => live_api::get_major_versions()?.into_iter().filter(|major| is_major_between(major, from, to)).collect(),
+    };
+    let mut releases = Vec::new();
+    for major in majors {
+        let filter = ReleaseFilter {
+            stream: Vec::new(),
+            platform: vec![system_info::get_platform().into()],
+            architecture: Vec::new(),
+            entitlements: Vec::new(),
+            version: Some(major),
+            order: UnityReleaseOrder::RELEASE_DATE_ASC,
+            limit: None,
+        };
+        for release in live_api::get_release_list(&filter)? {
+            let version = match release.version.parse::<UnityVersion>() {
+                Ok(version) => version,
+                Err(_) => continue,
+            };
+            if &version <= from || &version > to {
+                continue;
+            }
+            releases.push((version, release.release_notes));
+        }
+    }
+    releases.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut notes = Vec::new();
+    for (version, release_notes) in releases {
+        let is_markdown = matches!(release_notes.type_, live_api::release_list::FileType::MD);
+        notes.push(ReleaseNotes {
+            version,
+            text: download_release_notes(&release_notes.url, is_markdown)?,
+        });
+    }
+    Ok(notes)
+}
+
+fn is_major_between(major: &str, from: &UnityVersion, to: &UnityVersion) -> bool {
+    let parts = major.split_once('.').and_then(|(major, minor)| Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?)));
+    parts.is_some_and(|parts| (from.major(), from.minor()) <= parts && parts <= (to.major(), to.minor()))
+}
+
+fn download_release_notes(url: &str, is_markdown: bool) -> Result<String, Box<dyn Error>> {
+    let response = network::get(url)?;
+    if !response.status().is_success() {
+        return Err(format!("Couldn't download release notes from {}", url).into());
+    }
+    let text = response.text()?;
+    match is_markdown {
+        true => render_markdown(&text),
+        false => Ok(text),
+    }
+}
+
+fn render_markdown(markdown: &str) -> Result<String, Box<dyn Error>> {
+    let link = Regex::new(r"!?\[([^\]]*)\]\([^)]*\)")?;
+    let html_tag = Regex::new(r"</?[a-zA-Z][^>]*>")?;
+    let emphasis = Regex::new(r"(\*\*|__|`)")?;
+    let mut lines = Vec::new();
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let line = match trimmed.strip_prefix('#') {
+            Some(heading) => heading.trim_start_matches('#').trim().to_uppercase(),
+            None => match trimmed.strip_prefix("* ").or(trimmed.strip_prefix("- ")) {
+                Some(item) => format!("{}- {}", &line[..line.len() - trimmed.len()], item),
+                None => String::from(line),
+            },
+        };
+        let line = link.replace_all(&line, "$1");
+        let line = html_tag.replace_all(&line, "");
+        lines.push(emphasis.replace_all(&line, "").trim_end().to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    fn release(server_url: &str, version: &str) -> Value {
+        json!({
+            "__typename": "UnityRelease",
+            "version": version,
+            "releaseDate": "2024-01-01T00:00:00.000Z",
+            "stream": "LTS",
+            "recommended": false,
+            "shortRevision": "abcdef123456",
+            "entitlements": [],
+            "releaseNotes": { "url": format!("{}/notes/{}.txt", server_url, version), "integrity": null, "type": "TXT" },
+            "downloads": []
+        })
+    }
+
+    #[test]
+    fn queries_only_the_majors_between_the_versions() {
+        let queried_majors = Arc::new(Mutex::new(Vec::new()));
+        let server_queried_majors = queried_majors.clone();
+        let server_url = Arc::new(Mutex::new(String::new()));
+        let handler_server_url = server_url.clone();
+        let server = TestServer::start(move |request| {
+            if let Some(version) = request.path.strip_prefix("/notes/") {
+                return TestResponse::new(200, format!("Notes of {}", version.trim_end_matches(".txt")));
+            }
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let data = match body["operationName"].as_str().unwrap() {
+                "MajorVersionList" => {
+                    let majors: Vec<Value> = ["2021.3", "2022.1", "2022.2", "2022.3", "2023.1", "6000.0"].iter().map(|version| json!({ "version": version })).collect();
+                    json!({ "getUnityReleaseMajorVersions": majors })
+                }
+                _ => {
+                    let major = body["variables"]["version"].as_str().unwrap().to_string();
+                    server_queried_majors.lock().unwrap().push(major.clone());
+                    let server_url = handler_server_url.lock().unwrap().clone();
+                    let edges: Vec<Value> = [1, 5, 9].iter().map(|patch| json!({ "node": release(&server_url, &format!("{}.{}f1", major, patch)) })).collect();
+                    json!({ "getUnityReleases": { "totalCount": edges.len(), "pageInfo": { "hasNextPage": false }, "edges": edges } })
+                }
+            };
+            TestResponse::new(200, json!({ "data": data }).to_string())
+        });
+        *server_url.lock().unwrap() = server.url("");
+        let _config = test_support::set_test_config(Config { api_url: server.url("/graphql"), refresh: true, ..Config::default() });
+
+        let notes = get_release_notes_between(&"2022.3.5f1".parse().unwrap(), &"2022.1.1f1".parse().unwrap()).unwrap();
+        assert_eq!(*queried_majors.lock().unwrap(), ["2022.1", "2022.2", "2022.3"]);
+        let versions: Vec<String> = notes.iter().map(|notes| notes.version.to_string()).collect();
+        assert_eq!(versions, ["2022.1.5f1", "2022.1.9f1", "2022.2.1f1", "2022.2.5f1", "2022.2.9f1", "2022.3.1f1", "2022.3.5f1"]);
+        assert_eq!(notes[0].text, "Notes of 2022.1.5f1");
+    }
+}
@@ -0,0 +1,164 @@
+use crate::editors::info::SystemArch;
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const ELF_MACHINE_X86_64: u16 = 62;
+const ELF_MACHINE_AARCH64: u16 = 183;
+const MACH_O_MAGIC_64: u32 = 0xfeedfacf;
+const MACH_O_FAT_MAGIC: u32 = 0xcafebabe;
+const MACH_O_FAT_MAGIC_64: u32 = 0xcafebabf;
+const MACH_O_CPU_TYPE_X86_64: u32 = 0x01000007;
+const MACH_O_CPU_TYPE_ARM64: u32 = 0x0100000c;
+const PE_MACHINE_X86_64: u16 = 0x8664;
+const PE_MACHINE_ARM64: u16 = 0xaa64;
+
+/// Reads the architectures an executable was built for from its ELF, Mach-O (thin or universal) or PE header.
+pub fn read_executable_archs(path: impl AsRef<Path>) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+    let mut file = File::open(path.as_ref())?;
+    let mut header = [0u8; 8];
+    if file.read_exact(&mut header).is_err() {
+        return Err(format!("{} isn't a supported executable", path.as_ref().display()).into());
+    }
+
+    if header[..4] == ELF_MAGIC {
+        return read_elf_archs(&mut file, header[5]);
+    }
+    let magic = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    if magic == MACH_O_FAT_MAGIC || magic == MACH_O_FAT_MAGIC_64 {
+        let arch_count = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        return read_fat_archs(&mut file, arch_count, magic == MACH_O_FAT_MAGIC_64);
+    }
+    if magic == MACH_O_MAGIC_64 {
+        return Ok(get_mach_o_arch(u32::from_be_bytes([header[4], header[5], header[6], header[7]])).into_iter().collect());
+    }
+    if magic.swap_bytes() == MACH_O_MAGIC_64 {
+        return Ok(get_mach_o_arch(u32::from_le_bytes([header[4], header[5], header[6], header[7]])).into_iter().collect());
+    }
+    if header[..2] == *b"MZ" {
+        return read_pe_archs(&mut file);
+    }
+    Err(format!("{} isn't a supported executable", path.as_ref().display()).into())
+}
+
+fn read_elf_archs(file: &mut File, data_encoding: u8) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+    let mut machine = [0u8; 2];
+    file.seek(SeekFrom::Start(18))?;
+    file.read_exact(&mut machine)?;
+    let machine = match data_encoding {
+        2 => u16::from_be_bytes(machine),
+        _ => u16::from_le_bytes(machine),
+    };
+    match machine {
+        ELF_MACHINE_X86_64 => Ok(vec![SystemArch::X86_64]),
+        ELF_MACHINE_AARCH64 => Ok(vec![SystemArch::ARM64]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn read_fat_archs(file: &mut File, arch_count: u32, is_64_bit: bool) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+    let entry_size = if is_64_bit { 32 } else { 20 };
+    let mut archs = Vec::new();
+    for index in 0..arch_count as u64 {
+        let mut cpu_type = [0u8; 4];
+        file.seek(SeekFrom::Start(8 + index * entry_size))?;
+        file.read_exact(&mut cpu_type)?;
+        if let Some(arch) = get_mach_o_arch(u32::from_be_bytes(cpu_type)) {
+            if !archs.contains(&arch) {
+                archs.push(arch);
+            }
+        }
+    }
+    Ok(archs)
+}
+
+fn read_pe_archs(file: &mut File) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+    let mut header_offset = [0u8; 4];
+    file.seek(SeekFrom::Start(0x3c))?;
+    file.read_exact(&mut header_offset)?;
+    let mut header = [0u8; 6];
+    file.seek(SeekFrom::Start(u32::from_le_bytes(header_offset) as u64))?;
+    file.read_exact(&mut header)?;
+    if header[..4] != *b"PE\0\0" {
+        return Ok(Vec::new());
+    }
+    match u16::from_le_bytes([header[4], header[5]]) {
+        PE_MACHINE_X86_64 => Ok(vec![SystemArch::X86_64]),
+        PE_MACHINE_ARM64 => Ok(vec![SystemArch::ARM64]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn get_mach_o_arch(cpu_type: u32) -> Option<SystemArch> {
+    match cpu_type {
+        MACH_O_CPU_TYPE_X86_64 => Some(SystemArch::X86_64),
+        MACH_O_CPU_TYPE_ARM64 => Some(SystemArch::ARM64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn get_fixture_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("executables").join(name)
+    }
+
+    fn read_fixture_archs(name: &str) -> Vec<String> {
+        read_executable_archs(get_fixture_path(name)).unwrap().into_iter().map(String::from).collect()
+    }
+
+    fn read_truncated(name: &str, length: usize) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join(name);
+        fs::write(&path, &fs::read(get_fixture_path(name)).unwrap()[..length]).unwrap();
+        read_executable_archs(path)
+    }
+
+    #[test]
+    fn reads_elf_archs() {
+        assert_eq!(read_fixture_archs("elf_x86_64"), ["x86_64"]);
+        assert_eq!(read_fixture_archs("elf_arm64"), ["arm64"]);
+    }
+
+    #[test]
+    fn reads_thin_mach_o_archs() {
+        assert_eq!(read_fixture_archs("macho_x86_64"), ["x86_64"]);
+        assert_eq!(read_fixture_archs("macho_arm64"), ["arm64"]);
+    }
+
+    #[test]
+    fn reads_universal_mach_o_archs() {
+        assert_eq!(read_fixture_archs("macho_universal"), ["x86_64", "arm64"]);
+    }
+
+    #[test]
+    fn reads_pe_archs() {
+        assert_eq!(read_fixture_archs("pe_x86_64"), ["x86_64"]);
+        assert_eq!(read_fixture_archs("pe_arm64"), ["arm64"]);
+    }
+
+    #[test]
+    fn truncated_headers_are_errors() {
+        assert!(read_truncated("elf_x86_64", 4).is_err());
+        assert!(read_truncated("elf_x86_64", 19).is_err());
+        assert!(read_truncated("macho_x86_64", 6).is_err());
+        assert!(read_truncated("macho_universal", 30).is_err());
+        assert!(read_truncated("pe_x86_64", 0x3e).is_err());
+        assert!(read_truncated("pe_x86_64", 0x44).is_err());
+    }
+
+    #[test]
+    fn other_files_are_errors() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("script.sh");
+        fs::write(&path, "#!/bin/sh\necho hello\n").unwrap();
+        assert!(read_executable_archs(path).is_err());
+    }
+}
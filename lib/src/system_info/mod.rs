@@ -1,5 +1,6 @@
 use crate::editors::info::SystemArch;
 use crate::live_api::release_info::UnityReleaseDownloadPlatform;
+use crate::progress;
 use directories::BaseDirs;
 use std::error::Error;
 use std::fs;
@@ -9,6 +10,7 @@ use std::path::{Path, PathBuf};
 #[cfg_attr(target_os = "windows", path = "os/windows.rs")]
 #[cfg_attr(target_os = "linux", path = "os/linux.rs")]
 mod os;
+mod binary;
 
 pub fn get_platform() -> UnityReleaseDownloadPlatform {
     os::get_platform()
@@ -29,17 +31,26 @@ pub fn get_config_path() -> PathBuf {
     let base_dirs = BaseDirs::new().unwrap();
     let path = PathBuf::from(base_dirs.config_dir()).join(os::get_config_folder_name());
     if let Err(err) = fs::create_dir_all(&path) {
-        eprintln!("Warning: failed to create Unity Hub config directory {}: {}", path.display(), err);
+        progress::warn(format!("failed to create Unity Hub config directory {}: {}", path.display(), err));
     }
     path
 }
+
+pub fn get_wrum_config_path() -> PathBuf {
+    let path = get_config_path().join("wrum");
+    if let Err(err) = fs::create_dir_all(&path) {
+        progress::warn(format!("failed to create wrum config directory {}: {}", path.display(), err));
+    }
+    path
+}
+
 pub fn get_default_install_path() -> PathBuf {
     let mut path = os::get_applications_path();
     path.push("Unity");
     path.push("Hub");
     path.push("Editor");
     if let Err(err) = fs::create_dir_all(&path) {
-        eprintln!("Warning: failed to create default Unity install directory {}: {}", path.display(), err);
+        progress::warn(format!("failed to create default Unity install directory {}: {}", path.display(), err));
     }
     path
 }
@@ -48,10 +59,43 @@ pub fn get_editor_install_move_path(editor_path: impl AsRef<Path>) -> Option<Pat
     os::get_editor_install_move_path(editor_path)
 }
 
+pub fn get_archive_ini_platform(arch: SystemArch) -> String {
+    os::get_archive_ini_platform(arch)
+}
+
+pub fn get_archive_module_destination(url: &str) -> String {
+    os::get_archive_module_destination(url)
+}
+
 pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     os::get_editor_executable_path(editor_path)
 }
 
-pub fn get_editor_executable_arch(editor_path: impl AsRef<Path>) -> Result<SystemArch, Box<dyn Error>> {
-    os::get_editor_executable_arch(editor_path)
+pub fn get_editor_data_path(editor_path: impl AsRef<Path>) -> PathBuf {
+    os::get_editor_data_path(editor_path)
+}
+
+pub fn get_hub_editor_location(editor_path: impl AsRef<Path>) -> PathBuf {
+    os::get_hub_editor_location(editor_path)
+}
+
+pub fn get_editor_path_from_hub_location(location: impl AsRef<Path>) -> Option<PathBuf> {
+    os::get_editor_path_from_hub_location(location)
+}
+
+pub fn get_executable_archs(executable_path: impl AsRef<Path>) -> Result<Vec<SystemArch>, Box<dyn Error>> {
+    binary::read_executable_archs(executable_path)
+}
+
+pub fn get_editor_executable_arch(executable_path: impl AsRef<Path>) -> Result<SystemArch, Box<dyn Error>> {
+    let archs = get_executable_archs(executable_path.as_ref())?;
+    let preferable_arch = get_preferable_editor_arch();
+    if archs.contains(&preferable_arch) {
+        return Ok(preferable_arch);
+    }
+    archs.into_iter().next().ok_or(format!("Couldn't detect the architecture of {}", executable_path.as_ref().display()).into())
+}
+
+pub fn is_executable_running(executable_path: impl AsRef<Path>) -> Result<bool, Box<dyn Error>> {
+    os::is_executable_running(executable_path)
 }
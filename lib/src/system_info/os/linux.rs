@@ -2,6 +2,7 @@ use crate::editors::info::SystemArch;
 use crate::live_api::release_info::UnityReleaseDownloadPlatform;
 use directories::BaseDirs;
 use std::error::Error;
+use std::fs;
 use std::path::{Path, PathBuf};
 
 pub fn get_platform() -> UnityReleaseDownloadPlatform {
@@ -25,6 +26,14 @@ pub fn get_editor_install_move_path(_editor_path: impl AsRef<Path>) -> Option<Pa
     None
 }
 
+pub fn get_archive_ini_platform(_arch: SystemArch) -> String {
+    String::from("linux")
+}
+
+pub fn get_archive_module_destination(_url: &str) -> String {
+    String::from("{UNITY_PATH}")
+}
+
 pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     let mut executable_path = PathBuf::from(editor_path.as_ref());
     executable_path.push("Editor");
@@ -32,6 +41,27 @@ pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     executable_path
 }
 
-pub fn get_editor_executable_arch(_editor_path: impl AsRef<Path>) -> Result<SystemArch, Box<dyn Error>> {
-    Ok(SystemArch::X86_64)
+pub fn get_editor_data_path(editor_path: impl AsRef<Path>) -> PathBuf {
+    editor_path.as_ref().join("Editor").join("Data")
+}
+
+pub fn get_hub_editor_location(editor_path: impl AsRef<Path>) -> PathBuf {
+    get_editor_executable_path(editor_path)
+}
+
+pub fn get_editor_path_from_hub_location(location: impl AsRef<Path>) -> Option<PathBuf> {
+    location.as_ref().parent()?.parent().map(PathBuf::from)
+}
+
+pub fn is_executable_running(executable_path: impl AsRef<Path>) -> Result<bool, Box<dyn Error>> {
+    let executable_path = fs::canonicalize(executable_path)?;
+    for entry in fs::read_dir("/proc")? {
+        let process_exe = entry?.path().join("exe");
+        if let Ok(process_executable) = fs::read_link(process_exe) {
+            if process_executable == executable_path {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
 }
@@ -29,6 +29,20 @@ pub fn get_editor_install_move_path(editor_path: impl AsRef<Path>) -> Option<Pat
     Some(unpacked_path)
 }
 
+pub fn get_archive_ini_platform(arch: SystemArch) -> String {
+    match arch {
+        SystemArch::ARM64 => String::from("osx-arm64"),
+        SystemArch::X86_64 => String::from("osx"),
+    }
+}
+
+pub fn get_archive_module_destination(url: &str) -> String {
+    match url.to_lowercase().ends_with(".pkg") {
+        true => String::from("{UNITY_PATH}/PlaybackEngines"),
+        false => String::from("{UNITY_PATH}"),
+    }
+}
+
 pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     let mut executable_path = PathBuf::from(editor_path.as_ref());
     executable_path.push("Unity.app");
@@ -38,12 +52,20 @@ pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     executable_path
 }
 
-pub fn get_editor_executable_arch(editor_path: impl AsRef<Path>) -> Result<SystemArch, Box<dyn Error>> {
-    let file_info_output = Command::new("file").arg("--b").arg(editor_path.as_ref()).output()?.stdout;
-    let file_info = String::from_utf8(file_info_output.clone())?;
-    let file_info = file_info.trim().split(" ").last();
-    match file_info {
-        Some("arm64") => Ok(SystemArch::ARM64),
-        _ => Ok(SystemArch::X86_64),
-    }
+pub fn get_editor_data_path(editor_path: impl AsRef<Path>) -> PathBuf {
+    editor_path.as_ref().join("Unity.app").join("Contents")
+}
+
+pub fn get_hub_editor_location(editor_path: impl AsRef<Path>) -> PathBuf {
+    editor_path.as_ref().join("Unity.app")
+}
+
+pub fn get_editor_path_from_hub_location(location: impl AsRef<Path>) -> Option<PathBuf> {
+    location.as_ref().parent().map(PathBuf::from)
+}
+
+pub fn is_executable_running(executable_path: impl AsRef<Path>) -> Result<bool, Box<dyn Error>> {
+    let process_list_output = Command::new("ps").arg("-axo").arg("comm=").output()?.stdout;
+    let process_list = String::from_utf8(process_list_output)?;
+    Ok(process_list.lines().any(|process| Path::new(process.trim()) == executable_path.as_ref()))
 }
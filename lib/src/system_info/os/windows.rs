@@ -2,6 +2,7 @@ use crate::editors::info::SystemArch;
 use crate::live_api::release_info::UnityReleaseDownloadPlatform;
 use std::error::Error;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub fn get_platform() -> UnityReleaseDownloadPlatform {
     UnityReleaseDownloadPlatform::WINDOWS
@@ -23,6 +24,14 @@ pub fn get_editor_install_move_path(_editor_path: impl AsRef<Path>) -> Option<Pa
     None
 }
 
+pub fn get_archive_ini_platform(_arch: SystemArch) -> String {
+    String::from("win")
+}
+
+pub fn get_archive_module_destination(_url: &str) -> String {
+    String::from("{UNITY_PATH}")
+}
+
 pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     let mut executable_path = PathBuf::from(editor_path.as_ref());
     executable_path.push("Editor");
@@ -30,6 +39,26 @@ pub fn get_editor_executable_path(editor_path: impl AsRef<Path>) -> PathBuf {
     executable_path
 }
 
-pub fn get_editor_executable_arch(_editor_path: impl AsRef<Path>) -> Result<SystemArch, Box<dyn Error>> {
-    Ok(SystemArch::X86_64)
+pub fn get_editor_data_path(editor_path: impl AsRef<Path>) -> PathBuf {
+    editor_path.as_ref().join("Editor").join("Data")
+}
+
+pub fn get_hub_editor_location(editor_path: impl AsRef<Path>) -> PathBuf {
+    get_editor_executable_path(editor_path)
+}
+
+pub fn get_editor_path_from_hub_location(location: impl AsRef<Path>) -> Option<PathBuf> {
+    location.as_ref().parent()?.parent().map(PathBuf::from)
+}
+
+pub fn is_executable_running(executable_path: impl AsRef<Path>) -> Result<bool, Box<dyn Error>> {
+    let process_list_output = Command::new("powershell")
+        .arg("-NoProfile")
+        .arg("-Command")
+        .arg("Get-Process -Name Unity -ErrorAction SilentlyContinue | ForEach-Object { $_.Path }")
+        .output()?
+        .stdout;
+    let process_list = String::from_utf8(process_list_output)?;
+    let executable_path = executable_path.as_ref().to_string_lossy().to_lowercase();
+    Ok(process_list.lines().any(|process| process.trim().to_lowercase() == executable_path))
 }
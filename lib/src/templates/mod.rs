@@ -0,0 +1,76 @@
+use crate::install::integrity;
+use crate::install::unpack;
+use crate::{live_api, network};
+use crate::live_api::release_info::FileType;
+use crate::live_api::templates::{TemplateItem, TemplateItemVersions};
+use crate::version::UnityVersion;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+
+static TEMPLATE_PROJECT_DATA_PATH: [&str; 2] = ["package", "ProjectData~"];
+
+pub fn list_templates(editor_version: &UnityVersion) -> Result<Vec<TemplateItem>, Box<dyn Error>> {
+    let minor_version = format!("{}.{}", editor_version.major(), editor_version.minor());
+    let templates = live_api::get_templates(vec![editor_version.to_string(), minor_version])?;
+    Ok(templates
+        .into_iter()
+        .filter_map(|mut template| {
+            template.versions.retain(|template_version| is_version_supported(template_version, editor_version));
+            match template.versions.is_empty() {
+                true => None,
+                false => Some(template),
+            }
+        })
+        .collect())
+}
+
+pub fn find_template(package_name: &str, template_version: Option<&str>, editor_version: &UnityVersion) -> Result<(TemplateItem, TemplateItemVersions), Box<dyn Error>> {
+    let template = list_templates(editor_version)?
+        .into_iter()
+        .find(|template| template.package_name == package_name)
+        .ok_or(format!("Couldn't find template {} compatible with {}", package_name, editor_version))?;
+    let version = match template_version {
+        Some(template_version) => template.versions.iter().find(|version| version.name == template_version),
+        None => template.versions.iter().find(|version| version.is_latest).or(template.versions.last()),
+    }
+    .cloned()
+    .ok_or(format!("Couldn't find version {} of template {}", template_version.unwrap_or("latest"), package_name))?;
+    Ok((template, version))
+}
+
+pub fn apply_template(template_version: &TemplateItemVersions, project_path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let tarball = &template_version.tarball.template_file;
+    let temp_dir = TempDir::new()?;
+    let tarball_path = temp_dir.path().join("template.tgz");
+    let mut response = network::get(&tarball.url)?;
+    if !response.status().is_success() {
+        return Err(format!("Couldn't download template from {}", tarball.url).into());
+    }
+    std::io::copy(&mut response, &mut File::create(&tarball_path)?)?;
+    if !integrity::verify_file(&tarball_path, &tarball.integrity)? {
+        return Err(format!("Template {} doesn't match its integrity hash", tarball.url).into());
+    }
+
+    let unpack_path = temp_dir.path().join("unpacked");
+    unpack::unpack(FileType::TAR_GZ, &tarball_path, &unpack_path)?;
+    let project_data_path = TEMPLATE_PROJECT_DATA_PATH.iter().fold(unpack_path, |path, part| path.join(part));
+    if !project_data_path.exists() {
+        return Err("Template doesn't contain any project data".into());
+    }
+    fs::create_dir_all(&project_path)?;
+    unpack::move_files(project_data_path, project_path)?;
+    Ok(())
+}
+
+fn is_version_supported(template_version: &TemplateItemVersions, editor_version: &UnityVersion) -> bool {
+    let minor_version = format!("{}.{}", editor_version.major(), editor_version.minor());
+    let full_version = editor_version.to_string();
+    template_version.supported_unity_editor_versions.is_empty()
+        || template_version
+            .supported_unity_editor_versions
+            .iter()
+            .any(|supported| supported == &full_version || supported == &minor_version || full_version.starts_with(&format!("{}.", supported)))
+}
@@ -0,0 +1,204 @@
+// Helpers shared by the unit tests, not every test module uses all of them.
+#![allow(dead_code)]
+
+use crate::config;
+use crate::config::Config;
+use crate::progress;
+use crate::progress::{ProgressEvent, ProgressReporter};
+use std::collections::HashMap;
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+static TEST_HOME: OnceLock<TempDir> = OnceLock::new();
+
+/// Sets the global config for the duration of a test. Tests touching the config or the reporter
+/// run one at a time, with the user folders pointing to a temporary home.
+pub(crate) fn set_test_config(config: Config) -> MutexGuard<'static, ()> {
+    let guard = CONFIG_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    get_test_home();
+    config::set_config(config);
+    guard
+}
+
+pub(crate) fn get_test_home() -> &'static Path {
+    TEST_HOME
+        .get_or_init(|| {
+            let home = TempDir::new().expect("temporary home");
+            env::set_var("HOME", home.path());
+            env::set_var("XDG_CONFIG_HOME", home.path().join(".config"));
+            home
+        })
+        .path()
+}
+
+/// Keeps the reported events so tests can check them. Events of tests running alongside can be mixed in.
+pub(crate) struct RecordingReporter {
+    events: Mutex<Vec<ProgressEvent>>,
+}
+
+impl RecordingReporter {
+    /// Takes the events reported so far, formatted with Debug.
+    pub fn take_events(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap()).iter().map(|event| format!("{:?}", event)).collect()
+    }
+}
+
+impl ProgressReporter for RecordingReporter {
+    fn report(&self, event: &ProgressEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+pub(crate) fn record_progress() -> Arc<RecordingReporter> {
+    let reporter = Arc::new(RecordingReporter { events: Mutex::new(Vec::new()) });
+    progress::set_reporter(reporter.clone());
+    reporter
+}
+
+pub(crate) struct TestRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl TestRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+pub(crate) struct TestResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Closes the connection after this many body bytes while still announcing the full length.
+    pub truncate_at: Option<usize>,
+    pub delay: Duration,
+}
+
+impl TestResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        TestResponse { status, headers: Vec::new(), body: body.into(), truncate_at: None, delay: Duration::ZERO }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+
+    pub fn truncated(mut self, length: usize) -> Self {
+        self.truncate_at = Some(length);
+        self
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+type Handler = dyn Fn(&TestRequest) -> TestResponse + Send + Sync;
+
+/// A minimal HTTP/1.1 server on a random local port, answering one request per connection.
+pub(crate) struct TestServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<TestRequest>>>,
+}
+
+impl TestServer {
+    pub fn start(handler: impl Fn(&TestRequest) -> TestResponse + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("local test server");
+        let port = listener.local_addr().expect("local address").port();
+        let handler: Arc<Handler> = Arc::new(handler);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let server_requests = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let handler = handler.clone();
+                let requests = server_requests.clone();
+                thread::spawn(move || {
+                    if let Some(request) = handle_connection(stream, handler.as_ref()) {
+                        requests.lock().unwrap().push(request);
+                    }
+                });
+            }
+        });
+        TestServer { port, requests }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, path)
+    }
+
+    pub fn host(&self) -> String {
+        format!("127.0.0.1:{}", self.port)
+    }
+
+    /// Takes the requests answered so far.
+    pub fn take_requests(&self) -> Vec<TestRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, handler: &Handler) -> Option<TestRequest> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let content_length = headers.get("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    let request = TestRequest { method, path, headers, body };
+
+    let response = handler(&request);
+    thread::sleep(response.delay);
+    let mut head = format!("HTTP/1.1 {} Test\r\nContent-Length: {}\r\nConnection: close\r\n", response.status, response.body.len());
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    let body = match (request.method.as_str(), response.truncate_at) {
+        ("HEAD", _) => &response.body[..0],
+        (_, Some(length)) => &response.body[..length.min(response.body.len())],
+        _ => &response.body[..],
+    };
+    // The client may hang up early, e.g. after a timeout, which is fine for the tests.
+    let _ = stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(body)).and_then(|_| stream.flush());
+    Some(request)
+}
+
+/// Serves `data` honoring a `Range: bytes=start-end` header, like a static file server with `Accept-Ranges`.
+pub(crate) fn serve_range(request: &TestRequest, data: &[u8]) -> TestResponse {
+    let range = request.header("range").and_then(|range| range.strip_prefix("bytes=")).and_then(|range| range.split_once('-'));
+    let response = match range {
+        None => TestResponse::new(200, data),
+        Some((start, end)) => {
+            let start: usize = start.parse().unwrap_or(0);
+            let end: usize = end.parse().unwrap_or(data.len() - 1).min(data.len() - 1);
+            TestResponse::new(206, &data[start..=end]).with_header("Content-Range", &format!("bytes {}-{}/{}", start, end, data.len()))
+        }
+    };
+    response.with_header("Accept-Ranges", "bytes")
+}
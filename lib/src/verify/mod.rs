@@ -0,0 +1,280 @@
+use crate::editors::info::EditorInfo;
+use crate::install::integrity;
+use crate::live_api::release_info::FileType;
+use crate::modules::info::ModuleInfo;
+use crate::modules::manifest::ModuleManifest;
+use crate::modules::{manifest, remove_module_file};
+use crate::progress::ProgressEvent;
+use crate::version::UnityVersion;
+use crate::{editors, install, modules, progress};
+use reqwest::Url;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentReport {
+    pub id: String,
+    pub is_editor: bool,
+    pub tracked: bool,
+    pub missing: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+}
+
+impl ComponentReport {
+    pub fn is_broken(&self) -> bool {
+        !self.missing.is_empty() || !self.modified.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+pub struct VerifyReport {
+    #[serde(flatten)]
+    pub editor: EditorInfo,
+    pub path: PathBuf,
+    pub components: Vec<ComponentReport>,
+}
+
+impl VerifyReport {
+    pub fn is_broken(&self) -> bool {
+        self.components.iter().any(ComponentReport::is_broken)
+    }
+}
+
+pub fn verify_editor(version: &UnityVersion, arch: Option<String>) -> Result<VerifyReport, Box<dyn Error>> {
+    let editor = editors::find_recorded_editor(version, arch)?.ok_or(format!("{} is not installed", version))?;
+    let mut components = vec![verify_component(&editor.path, &version.to_string(), true)?];
+    if !editor.executable_path.exists() {
+        let executable_path = editor.executable_path.strip_prefix(&editor.path).unwrap_or(&editor.executable_path).to_path_buf();
+        if !components[0].missing.contains(&executable_path) {
+            components[0].missing.push(executable_path);
+        }
+    }
+    if editor.path.join("modules.json").exists() {
+        let mut selected_modules: Vec<String> = modules::load_modules_from_disk(&editor.path)?
+            .into_iter()
+            .filter(|module| module.selected)
+            .map(|module| module.id)
+            .collect();
+        selected_modules.sort();
+        for module_id in selected_modules {
+            components.push(verify_component(&editor.path, &module_id, false)?);
+        }
+    }
+    Ok(VerifyReport {
+        path: editor.path.clone(),
+        editor,
+        components,
+    })
+}
+
+enum RepairSource<'a> {
+    Editor { url: String, file_type: FileType },
+    Module(&'a ModuleInfo),
+}
+
+/// Repairs the broken components by installing them again. Every component is checked to have a download to
+/// repair it from before any file is removed.
+pub fn repair_editor(report: &VerifyReport) -> Result<Vec<String>, Box<dyn Error>> {
+    let editor_path = &report.path;
+    let broken_components: Vec<&ComponentReport> = report.components.iter().filter(|component| component.is_broken()).collect();
+    let modules_info = match broken_components.iter().any(|component| !component.is_editor) {
+        true => modules::load_modules_from_disk(editor_path)?,
+        false => Vec::new(),
+    };
+    let mut repairs = Vec::new();
+    for component in broken_components {
+        let source = get_repair_source(&report.editor, component, &modules_info).map_err(|err| format!("Can't repair {}: {}", component.id, err))?;
+        repairs.push((component, source));
+    }
+
+    let mut repaired = Vec::new();
+    for (component, source) in repairs {
+        let lock_file = install::get_install_lock(&component.id, editor_path)?;
+        progress::report(ProgressEvent::RepairStarted { id: component.id.clone() });
+        if let Some(manifest) = manifest::read_manifest(editor_path, &component.id)? {
+            for file in &manifest.files {
+                remove_module_file(editor_path, &file.get_path(editor_path)?)?;
+            }
+        }
+        match source {
+            RepairSource::Editor { url, file_type } => {
+                let files = editors::install_editor_files(&url, report.editor.download_integrity.as_deref(), None, &report.editor.version, editor_path, file_type)?;
+                manifest::write_manifest(editor_path, &ModuleManifest::new(&component.id, editor_path, files)?)?;
+            }
+            RepairSource::Module(module) => modules::install_module_files(editor_path, module)?,
+        }
+        install::release_install_lock(lock_file)?;
+        repaired.push(component.id.clone());
+    }
+    Ok(repaired)
+}
+
+fn get_repair_source<'a>(editor: &EditorInfo, component: &ComponentReport, modules_info: &'a [ModuleInfo]) -> Result<RepairSource<'a>, Box<dyn Error>> {
+    if component.is_editor {
+        let url = editor.download_url.clone().ok_or("its download url isn't recorded in wrum.json. Reinstall the editor instead")?;
+        let file_type = editor.download_type.clone().ok_or("its download type isn't recorded in wrum.json. Reinstall the editor instead")?;
+        check_repair_url(&url)?;
+        return Ok(RepairSource::Editor { url, file_type });
+    }
+    let module = modules_info.iter().find(|module| module.id == component.id).ok_or("it isn't listed in modules.json")?;
+    check_repair_url(&module.url)?;
+    Ok(RepairSource::Module(module))
+}
+
+// Components installed from a local archive record its path, which may be gone or changed since.
+fn check_repair_url(url: &str) -> Result<(), Box<dyn Error>> {
+    if url.is_empty() {
+        return Err("its download url isn't recorded. Install it again instead".into());
+    }
+    match Url::parse(url) {
+        Ok(parsed_url) if matches!(parsed_url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("it was installed from {}, not from a download. Install it again from its archive instead", url).into()),
+    }
+}
+
+fn verify_component(editor_path: &Path, id: &str, is_editor: bool) -> Result<ComponentReport, Box<dyn Error>> {
+    let mut report = ComponentReport {
+        id: String::from(id),
+        is_editor,
+        tracked: false,
+        missing: Vec::new(),
+        modified: Vec::new(),
+    };
+    let manifest = match manifest::read_manifest(editor_path, id)? {
+        None => return Ok(report),
+        Some(manifest) => manifest,
+    };
+    report.tracked = true;
+    for file in manifest.files {
+        let file_path = file.get_path(editor_path)?;
+        let metadata = match fs::symlink_metadata(&file_path) {
+            Err(_) => {
+                report.missing.push(file.path);
+                continue;
+            }
+            Ok(metadata) => metadata,
+        };
+        let is_modified = match (&file.integrity, metadata.is_symlink()) {
+            (_, true) => false,
+            _ if metadata.len() != file.size => true,
+            (Some(file_integrity), false) => !integrity::verify_file(&file_path, file_integrity)?,
+            (None, false) => false,
+        };
+        if is_modified {
+            report.modified.push(file.path);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::editors::archive;
+    use crate::system_info;
+    use crate::test_support;
+    use crate::test_support::TestServer;
+    use std::io::{Cursor, Write};
+    use std::str::FromStr;
+
+    fn create_editor(version: &str, wrum_json: &str) -> PathBuf {
+        let editor_path = system_info::get_default_install_path().join(version);
+        let executable_path = system_info::get_editor_executable_path(&editor_path);
+        fs::create_dir_all(executable_path.parent().unwrap()).unwrap();
+        fs::write(executable_path, "").unwrap();
+        fs::write(editor_path.join("wrum.json"), wrum_json).unwrap();
+        editor_path
+    }
+
+    fn create_tracked_file(editor_path: &Path, id: &str, contents: &str) -> PathBuf {
+        let file_path = editor_path.join("Data").join(format!("{}.txt", id));
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, contents).unwrap();
+        manifest::write_manifest(editor_path, &ModuleManifest::new(id, editor_path, vec![file_path.clone()]).unwrap()).unwrap();
+        file_path
+    }
+
+    fn get_zip(file_name: &str, contents: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file(file_name, zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn add_module(editor_path: &Path, id: &str, url: &str) -> ModuleInfo {
+        let mut module = archive::get_local_archive_module(id, Path::new("module.zip"));
+        module.url = String::from(url);
+        module.destination = Some(String::from("{UNITY_PATH}/Data"));
+        module.selected = true;
+        modules::write_modules_info(editor_path, vec![module.clone()]).unwrap();
+        module
+    }
+
+    #[test]
+    fn detects_modified_and_missing_files() {
+        let _config = test_support::set_test_config(Config::default());
+        let version = UnityVersion::from_str("2021.3.1f1").unwrap();
+        let editor_path = create_editor("2021.3.1f1", r#"{"version":"2021.3.1f1","arch":"X86_64"}"#);
+        let file_path = editor_path.join("Data").join("a.txt");
+        let missing_path = editor_path.join("Data").join("b.txt");
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, "original").unwrap();
+        fs::write(&missing_path, "original").unwrap();
+        manifest::write_manifest(&editor_path, &ModuleManifest::new("2021.3.1f1", &editor_path, vec![file_path.clone(), missing_path.clone()]).unwrap()).unwrap();
+        assert!(!verify_editor(&version, None).unwrap().is_broken());
+
+        fs::write(&file_path, "modified").unwrap();
+        fs::remove_file(&missing_path).unwrap();
+        let report = verify_editor(&version, None).unwrap();
+        assert!(report.components[0].tracked);
+        assert_eq!(report.components[0].modified, [PathBuf::from("Data").join("a.txt")]);
+        assert_eq!(report.components[0].missing, [PathBuf::from("Data").join("b.txt")]);
+        fs::remove_dir_all(editor_path).unwrap();
+    }
+
+    #[test]
+    fn repair_downloads_broken_modules_again() {
+        let data = get_zip("android.txt", "original");
+        let server = TestServer::start(move |request| test_support::serve_range(request, &data));
+        let _config = test_support::set_test_config(Config::default());
+        let version = UnityVersion::from_str("2021.3.2f1").unwrap();
+        let editor_path = create_editor("2021.3.2f1", r#"{"version":"2021.3.2f1","arch":"X86_64"}"#);
+        let module = add_module(&editor_path, "android", &server.url("/android.zip"));
+        modules::install_module_files(&editor_path, &module).unwrap();
+        let file_path = editor_path.join("Data").join("android.txt");
+        fs::write(&file_path, "modified").unwrap();
+
+        let report = verify_editor(&version, None).unwrap();
+        assert_eq!(report.components[1].modified, [PathBuf::from("Data").join("android.txt")]);
+        assert_eq!(repair_editor(&report).unwrap(), ["android"]);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original");
+        assert!(!verify_editor(&version, None).unwrap().is_broken());
+        fs::remove_dir_all(editor_path).unwrap();
+    }
+
+    #[test]
+    fn repair_leaves_components_without_a_download_untouched() {
+        let _config = test_support::set_test_config(Config::default());
+        let version = UnityVersion::from_str("2021.3.3f1").unwrap();
+        let editor_path = create_editor("2021.3.3f1", r#"{"version":"2021.3.3f1","arch":"X86_64"}"#);
+        let editor_file_path = create_tracked_file(&editor_path, "2021.3.3f1", "original");
+        let module_file_path = create_tracked_file(&editor_path, "android", "original");
+        add_module(&editor_path, "android", &editor_path.join("android.zip").to_string_lossy());
+        fs::write(&editor_file_path, "modified").unwrap();
+        fs::write(&module_file_path, "modified").unwrap();
+
+        let err = repair_editor(&verify_editor(&version, None).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Can't repair 2021.3.3f1: its download url isn't recorded"), "{}", err);
+        assert_eq!(fs::read_to_string(&editor_file_path).unwrap(), "modified");
+
+        fs::write(&editor_file_path, "original").unwrap();
+        let err = repair_editor(&verify_editor(&version, None).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Can't repair android: it was installed from"), "{}", err);
+        assert_eq!(fs::read_to_string(&module_file_path).unwrap(), "modified");
+        fs::remove_dir_all(editor_path).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+pub mod selector;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static VERSION_PATTERN: &str = r"^(\d+)\.(\d+)\.(\d+)([abfp])(\d+)(?:c(\d+))?$";
+static EMBEDDED_VERSION_PATTERN: &str = r"\b\d+\.\d+\.\d+[abfp]\d+(?:c\d+)?\b";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum VersionStream {
+    Alpha,
+    Beta,
+    Final,
+    Patch,
+}
+
+impl VersionStream {
+    fn letter(&self) -> char {
+        match self {
+            VersionStream::Alpha => 'a',
+            VersionStream::Beta => 'b',
+            VersionStream::Final => 'f',
+            VersionStream::Patch => 'p',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct UnityVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    stream: VersionStream,
+    build: u32,
+    china_build: Option<u32>,
+}
+
+impl UnityVersion {
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+
+    pub fn stream(&self) -> VersionStream {
+        self.stream
+    }
+
+    pub fn build(&self) -> u32 {
+        self.build
+    }
+
+    pub fn china_build(&self) -> Option<u32> {
+        self.china_build
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        matches!(self.stream, VersionStream::Alpha | VersionStream::Beta)
+    }
+
+    /// Finds the first version embedded in a longer text, such as a file name or a download url.
+    pub fn find_in(text: &str) -> Option<UnityVersion> {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        let regex = REGEX.get_or_init(|| Regex::new(EMBEDDED_VERSION_PATTERN).unwrap());
+        regex.find_iter(text).find_map(|found| found.as_str().parse().ok())
+    }
+}
+
+impl FromStr for UnityVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        let regex = REGEX.get_or_init(|| Regex::new(VERSION_PATTERN).unwrap());
+        let captures = regex.captures(value.trim()).ok_or(format!("Invalid Unity version \"{}\"", value))?;
+        let number = |index: usize| captures[index].parse::<u32>().map_err(|_| format!("Invalid Unity version \"{}\"", value));
+        Ok(UnityVersion {
+            major: number(1)?,
+            minor: number(2)?,
+            patch: number(3)?,
+            stream: match &captures[4] {
+                "a" => VersionStream::Alpha,
+                "b" => VersionStream::Beta,
+                "p" => VersionStream::Patch,
+                _ => VersionStream::Final,
+            },
+            build: number(5)?,
+            china_build: match captures.get(6) {
+                None => None,
+                Some(_) => Some(number(6)?),
+            },
+        })
+    }
+}
+
+impl Display for UnityVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}{}{}", self.major, self.minor, self.patch, self.stream.letter(), self.build)?;
+        if let Some(china_build) = self.china_build {
+            write!(f, "c{}", china_build)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for UnityVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then(self.stream.cmp(&other.stream))
+            .then(self.build.cmp(&other.build))
+            .then(self.china_build.cmp(&other.china_build))
+    }
+}
+
+impl PartialOrd for UnityVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for UnityVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for UnityVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        UnityVersion::from_str(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(value: &str) -> UnityVersion {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_every_stream() {
+        for (value, stream) in [
+            ("2023.1.0a14", VersionStream::Alpha),
+            ("2023.1.0b3", VersionStream::Beta),
+            ("2022.3.10f1", VersionStream::Final),
+            ("2019.4.40p2", VersionStream::Patch),
+        ] {
+            let parsed = version(value);
+            assert_eq!(parsed.stream(), stream);
+            assert_eq!(parsed.to_string(), value);
+        }
+        let parsed = version(" 2022.3.10f1 ");
+        assert_eq!((parsed.major(), parsed.minor(), parsed.patch(), parsed.build()), (2022, 3, 10, 1));
+        assert!(version("2023.1.0b3").is_prerelease());
+        assert!(!version("2019.4.40p2").is_prerelease());
+    }
+
+    #[test]
+    fn parses_china_builds() {
+        let parsed = version("2022.3.10f1c1");
+        assert_eq!(parsed.china_build(), Some(1));
+        assert_eq!(parsed.to_string(), "2022.3.10f1c1");
+        assert_eq!(version("2022.3.10f1").china_build(), None);
+    }
+
+    #[test]
+    fn rejects_malformed_versions() {
+        for value in ["", "2022", "2022.3", "2022.3.10", "2022.3.10x1", "2022.3.10f", "2022.3.10f1c", "2022.3.10f1-beta", "v2022.3.10f1", "99999999999.1.1f1"] {
+            assert!(value.parse::<UnityVersion>().is_err(), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn orders_by_number_then_stream_then_build() {
+        let mut versions: Vec<UnityVersion> = ["2022.3.10f1c1", "2022.3.9f1", "2022.3.10p1", "2022.3.10f2", "2022.3.10a1", "2022.3.10f1", "2022.3.10b5", "2021.3.40f1", "6000.0.1f1"]
+            .into_iter()
+            .map(version)
+            .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(ToString::to_string).collect();
+        assert_eq!(sorted, ["2021.3.40f1", "2022.3.9f1", "2022.3.10a1", "2022.3.10b5", "2022.3.10f1", "2022.3.10f1c1", "2022.3.10f2", "2022.3.10p1", "6000.0.1f1"]);
+    }
+
+    #[test]
+    fn finds_versions_embedded_in_text() {
+        assert_eq!(UnityVersion::find_in("https://download.unity3d.com/download_unity/abc/Unity-2022.3.10f1.tar.xz"), Some(version("2022.3.10f1")));
+        assert_eq!(UnityVersion::find_in("Editor 2023.1.0b3c2 (x64)"), Some(version("2023.1.0b3c2")));
+        assert_eq!(UnityVersion::find_in("Unity 2022.3"), None);
+    }
+
+    #[test]
+    fn deserialization_rejects_invalid_versions() {
+        assert_eq!(serde_json::from_str::<UnityVersion>("\"2022.3.10f1\"").unwrap(), version("2022.3.10f1"));
+        assert!(serde_json::from_str::<UnityVersion>("\"latest\"").is_err());
+    }
+}
@@ -0,0 +1,383 @@
+use crate::live_api::release_list::{UnityReleaseOrder, UnityReleaseStream};
+use crate::live_api::ReleaseFilter;
+use crate::version::{UnityVersion, VersionStream};
+use crate::{live_api, system_info};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const LATEST_RELEASES_SAMPLE: usize = 25;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ComparatorOp {
+    Equal,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum VersionBound {
+    Full(UnityVersion),
+    Partial(Vec<u32>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Comparator {
+    op: ComparatorOp,
+    bound: VersionBound,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VersionSelector {
+    Exact(UnityVersion),
+    Lts,
+    Latest,
+    LatestBeta,
+    LatestAlpha,
+    Prefix(Vec<u32>),
+    Range(Vec<Comparator>),
+}
+
+impl VersionBound {
+    fn compare(&self, version: &UnityVersion) -> Ordering {
+        match self {
+            VersionBound::Full(bound) => version.cmp(bound),
+            VersionBound::Partial(parts) => {
+                let version_parts = [version.major(), version.minor(), version.patch()];
+                version_parts[..parts.len()].cmp(parts)
+            }
+        }
+    }
+}
+
+impl Comparator {
+    fn matches(&self, version: &UnityVersion) -> bool {
+        let ordering = self.bound.compare(version);
+        match self.op {
+            ComparatorOp::Equal => ordering == Ordering::Equal,
+            ComparatorOp::Greater => ordering == Ordering::Greater,
+            ComparatorOp::GreaterOrEqual => ordering != Ordering::Less,
+            ComparatorOp::Less => ordering == Ordering::Less,
+            ComparatorOp::LessOrEqual => ordering != Ordering::Greater,
+        }
+    }
+
+    fn parts(&self) -> Vec<u32> {
+        match &self.bound {
+            VersionBound::Full(version) => vec![version.major(), version.minor(), version.patch()],
+            VersionBound::Partial(parts) => parts.clone(),
+        }
+    }
+
+    fn allows_prerelease(&self) -> bool {
+        matches!(&self.bound, VersionBound::Full(version) if version.is_prerelease())
+    }
+}
+
+impl VersionSelector {
+    pub fn is_exact(&self) -> bool {
+        matches!(self, VersionSelector::Exact(_))
+    }
+
+    pub fn matches(&self, version: &UnityVersion) -> bool {
+        match self {
+            VersionSelector::Exact(exact) => exact == version,
+            VersionSelector::Lts | VersionSelector::Latest => !version.is_prerelease(),
+            VersionSelector::LatestBeta => version.stream() == VersionStream::Beta,
+            VersionSelector::LatestAlpha => version.stream() == VersionStream::Alpha,
+            VersionSelector::Prefix(parts) => {
+                !version.is_prerelease() && VersionBound::Partial(parts.clone()).compare(version) == Ordering::Equal
+            }
+            VersionSelector::Range(comparators) => {
+                (!version.is_prerelease() || comparators.iter().any(Comparator::allows_prerelease))
+                    && comparators.iter().all(|comparator| comparator.matches(version))
+            }
+        }
+    }
+
+    pub fn resolve(&self) -> Result<UnityVersion, Box<dyn Error>> {
+        if let VersionSelector::Exact(version) = self {
+            return Ok(version.clone());
+        }
+        let (stream, version, limit) = match self {
+            VersionSelector::Lts => (vec![UnityReleaseStream::LTS], None, Some(LATEST_RELEASES_SAMPLE)),
+            VersionSelector::Latest => (vec![UnityReleaseStream::LTS, UnityReleaseStream::TECH], None, Some(LATEST_RELEASES_SAMPLE)),
+            VersionSelector::LatestBeta => (vec![UnityReleaseStream::BETA], None, Some(LATEST_RELEASES_SAMPLE)),
+            VersionSelector::LatestAlpha => (vec![UnityReleaseStream::ALPHA], None, Some(LATEST_RELEASES_SAMPLE)),
+            VersionSelector::Prefix(parts) => {
+                let prefix = parts.iter().map(u32::to_string).collect::<Vec<String>>().join(".");
+                (vec![UnityReleaseStream::LTS, UnityReleaseStream::TECH], Some(prefix), None)
+            }
+            VersionSelector::Range(comparators) => {
+                let stream = match comparators.iter().any(Comparator::allows_prerelease) {
+                    true => Vec::new(),
+                    false => vec![UnityReleaseStream::LTS, UnityReleaseStream::TECH],
+                };
+                // The releases are ordered by date, so the highest match can be on any page.
+                (stream, get_range_prefix(comparators), None)
+            }
+            VersionSelector::Exact(_) => unreachable!(),
+        };
+        let filter = ReleaseFilter {
+            stream,
+            platform: vec![system_info::get_platform().into()],
+            architecture: Vec::new(),
+            entitlements: Vec::new(),
+            version,
+            order: UnityReleaseOrder::RELEASE_DATE_DESC,
+            limit,
+        };
+        let releases = live_api::get_release_list(&filter)?;
+        let versions = releases.iter().filter_map(|release| release.version.parse::<UnityVersion>().ok());
+        self.select(versions).ok_or(format!("Couldn't find any release matching \"{}\"", self).into())
+    }
+
+    pub fn select(&self, versions: impl IntoIterator<Item = UnityVersion>) -> Option<UnityVersion> {
+        versions.into_iter().filter(|version| self.matches(version)).max()
+    }
+}
+
+impl FromStr for ComparatorOp {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "" | "=" => Ok(ComparatorOp::Equal),
+            ">" => Ok(ComparatorOp::Greater),
+            ">=" => Ok(ComparatorOp::GreaterOrEqual),
+            "<" => Ok(ComparatorOp::Less),
+            "<=" => Ok(ComparatorOp::LessOrEqual),
+            _ => Err(format!("Invalid version comparator \"{}\"", value)),
+        }
+    }
+}
+
+impl FromStr for VersionBound {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(version) = value.parse::<UnityVersion>() {
+            return Ok(VersionBound::Full(version));
+        }
+        parse_partial_version(value).map(VersionBound::Partial)
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let bound_start = value.find(|c: char| c.is_ascii_digit()).ok_or(format!("Invalid version range \"{}\"", value))?;
+        Ok(Comparator {
+            op: value[..bound_start].trim().parse()?,
+            bound: value[bound_start..].trim().parse()?,
+        })
+    }
+}
+
+impl FromStr for VersionSelector {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "lts" => return Ok(VersionSelector::Lts),
+            "latest" => return Ok(VersionSelector::Latest),
+            "latest-beta" => return Ok(VersionSelector::LatestBeta),
+            "latest-alpha" => return Ok(VersionSelector::LatestAlpha),
+            _ => {}
+        }
+        if let Ok(version) = value.parse::<UnityVersion>() {
+            return Ok(VersionSelector::Exact(version));
+        }
+        if value.starts_with(|c: char| c.is_ascii_digit()) && !value.contains(',') {
+            if let Ok(parts) = parse_partial_version(value) {
+                return Ok(VersionSelector::Prefix(parts));
+            }
+        }
+        let comparators = value.split(',').map(str::parse).collect::<Result<Vec<Comparator>, String>>()?;
+        Ok(VersionSelector::Range(comparators))
+    }
+}
+
+impl Display for VersionBound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionBound::Full(version) => write!(f, "{}", version),
+            VersionBound::Partial(parts) => write!(f, "{}", parts.iter().map(u32::to_string).collect::<Vec<String>>().join(".")),
+        }
+    }
+}
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            ComparatorOp::Equal => "=",
+            ComparatorOp::Greater => ">",
+            ComparatorOp::GreaterOrEqual => ">=",
+            ComparatorOp::Less => "<",
+            ComparatorOp::LessOrEqual => "<=",
+        };
+        write!(f, "{}{}", op, self.bound)
+    }
+}
+
+impl Display for VersionSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionSelector::Exact(version) => write!(f, "{}", version),
+            VersionSelector::Lts => write!(f, "lts"),
+            VersionSelector::Latest => write!(f, "latest"),
+            VersionSelector::LatestBeta => write!(f, "latest-beta"),
+            VersionSelector::LatestAlpha => write!(f, "latest-alpha"),
+            VersionSelector::Prefix(parts) => write!(f, "{}", VersionBound::Partial(parts.clone())),
+            VersionSelector::Range(comparators) => {
+                write!(f, "{}", comparators.iter().map(Comparator::to_string).collect::<Vec<String>>().join(", "))
+            }
+        }
+    }
+}
+
+// Every version between a lower and an upper bound starts with the parts the bounds have in common,
+// so that prefix can narrow the release query. Ranges open on one side can't be narrowed.
+fn get_range_prefix(comparators: &[Comparator]) -> Option<String> {
+    let has_lower = comparators.iter().any(|comparator| matches!(comparator.op, ComparatorOp::Equal | ComparatorOp::Greater | ComparatorOp::GreaterOrEqual));
+    let has_upper = comparators.iter().any(|comparator| matches!(comparator.op, ComparatorOp::Equal | ComparatorOp::Less | ComparatorOp::LessOrEqual));
+    if !has_lower || !has_upper {
+        return None;
+    }
+    let mut prefix = comparators.first()?.parts();
+    for comparator in &comparators[1..] {
+        let parts = comparator.parts();
+        let common = prefix.iter().zip(&parts).take_while(|(a, b)| a == b).count();
+        prefix.truncate(common);
+    }
+    match prefix.is_empty() {
+        true => None,
+        false => Some(prefix.iter().map(u32::to_string).collect::<Vec<String>>().join(".")),
+    }
+}
+
+fn parse_partial_version(value: &str) -> Result<Vec<u32>, String> {
+    let parts = value
+        .split('.')
+        .map(|part| part.parse::<u32>().map_err(|_| format!("Invalid version selector \"{}\"", value)))
+        .collect::<Result<Vec<u32>, String>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(format!("Invalid version selector \"{}\"", value));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support;
+    use crate::test_support::{TestResponse, TestServer};
+    use serde_json::{json, Value};
+
+    fn selector(value: &str) -> VersionSelector {
+        value.parse().unwrap()
+    }
+
+    fn version(value: &str) -> UnityVersion {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_selectors() {
+        assert_eq!(selector("LTS"), VersionSelector::Lts);
+        assert_eq!(selector("latest"), VersionSelector::Latest);
+        assert_eq!(selector("latest-beta"), VersionSelector::LatestBeta);
+        assert_eq!(selector("latest-alpha"), VersionSelector::LatestAlpha);
+        assert_eq!(selector("2022.3.10f1"), VersionSelector::Exact(version("2022.3.10f1")));
+        assert_eq!(selector("2022.3"), VersionSelector::Prefix(vec![2022, 3]));
+        assert_eq!(selector("6000"), VersionSelector::Prefix(vec![6000]));
+        assert_eq!(selector(">=2022.3.5f1, <2022.3.20f1").to_string(), ">=2022.3.5f1, <2022.3.20f1");
+        assert_eq!(selector("2022.3.10f1,2022.3.11f1").to_string(), "=2022.3.10f1, =2022.3.11f1");
+    }
+
+    #[test]
+    fn rejects_invalid_selectors() {
+        for value in ["", "newest", "2022.3.10.1", ">>2022.3", "=>2022", ">=2022.x", "2022.3,"] {
+            assert!(value.parse::<VersionSelector>().is_err(), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn matches_versions() {
+        assert!(selector("2022.3").matches(&version("2022.3.10f1")));
+        assert!(!selector("2022.3").matches(&version("2022.30.1f1")));
+        assert!(!selector("2022.3").matches(&version("2022.3.0b1")));
+        assert!(selector("lts").matches(&version("2019.4.40p2")));
+        assert!(!selector("latest").matches(&version("2023.1.0a14")));
+        assert!(selector("latest-beta").matches(&version("2023.1.0b3")));
+        assert!(selector(">=2022.3.5f1, <2022.3.20f1").matches(&version("2022.3.5f1")));
+        assert!(!selector(">=2022.3.5f1, <2022.3.20f1").matches(&version("2022.3.20f1")));
+        assert!(selector("<=2022.3").matches(&version("2022.3.62f1")));
+        assert!(!selector("<2022.3").matches(&version("2022.3.0f1")));
+        assert!(!selector(">=2023.1").matches(&version("2023.2.0b1")));
+        assert!(selector(">=2023.1.0b1").matches(&version("2023.2.0b1")));
+    }
+
+    #[test]
+    fn selects_the_newest_match() {
+        let versions = ["2022.3.9f1", "2022.3.20f1", "2022.3.10f1", "2023.1.0b3", "2021.3.40f1"].map(version);
+        assert_eq!(selector("2022.3").select(versions.clone()), Some(version("2022.3.20f1")));
+        assert_eq!(selector(">2021, <2022.3.15f1").select(versions.clone()), Some(version("2022.3.10f1")));
+        assert_eq!(selector("latest-beta").select(versions.clone()), Some(version("2023.1.0b3")));
+        assert_eq!(selector("2020").select(versions), None);
+    }
+
+    #[test]
+    fn ranges_are_narrowed_to_their_common_prefix() {
+        let prefix = |value: &str| match selector(value) {
+            VersionSelector::Range(comparators) => get_range_prefix(&comparators),
+            other => panic!("{} isn't a range", other),
+        };
+        assert_eq!(prefix(">=2022.3.5f1, <2022.3.20f1"), Some(String::from("2022.3")));
+        assert_eq!(prefix(">=2022.1, <=2022.3"), Some(String::from("2022")));
+        assert_eq!(prefix(">=2021.3, <2022"), None);
+        assert_eq!(prefix(">=2022.3.5f1"), None);
+        assert_eq!(prefix("<2022.3"), None);
+    }
+
+    fn release(version: &str) -> Value {
+        json!({
+            "__typename": "UnityRelease",
+            "version": version,
+            "releaseDate": "2024-01-01T00:00:00.000Z",
+            "stream": "LTS",
+            "recommended": false,
+            "shortRevision": "abcdef123456",
+            "entitlements": [],
+            "releaseNotes": { "url": "https://example.com/notes.md", "integrity": null, "type": "MD" },
+            "downloads": []
+        })
+    }
+
+    #[test]
+    fn ranges_are_resolved_against_every_page_of_releases() {
+        // Newest first, like the API: the older release lines only show up on the last page.
+        let versions: Vec<String> = (0..50).rev().map(|patch| format!("2023.2.{}f1", patch)).chain((0..10).rev().map(|patch| format!("2021.3.{}f1", patch))).collect();
+        let server = TestServer::start(move |request| {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let skip = body["variables"]["skip"].as_u64().unwrap() as usize;
+            let limit = body["variables"]["limit"].as_u64().unwrap() as usize;
+            let edges: Vec<Value> = versions.iter().skip(skip).take(limit).map(|version| json!({ "node": release(version) })).collect();
+            let has_next_page = skip + limit < versions.len();
+            TestResponse::new(200, json!({ "data": { "getUnityReleases": { "totalCount": versions.len(), "pageInfo": { "hasNextPage": has_next_page }, "edges": edges } } }).to_string())
+        });
+        let _config = test_support::set_test_config(Config { api_url: server.url("/graphql"), refresh: true, ..Config::default() });
+
+        assert_eq!(selector("<2022").resolve().unwrap(), version("2021.3.9f1"));
+        assert_eq!(server.take_requests().len(), 3);
+        assert_eq!(selector(">=2021.3.5f1, <2023.2.1f1").resolve().unwrap(), version("2023.2.0f1"));
+    }
+}
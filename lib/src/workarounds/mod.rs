@@ -1,15 +1,17 @@
-use crate::editors;
+use crate::progress::ProgressEvent;
+use crate::{editors, progress};
+use crate::version::UnityVersion;
 use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
 #[cfg(target_os = "windows")]
-fn apply_bee_workaround(version: &str, arch: Option<String>) -> Result<(), Box<dyn Error>> {
+fn apply_bee_workaround(version: &UnityVersion, arch: Option<String>) -> Result<(), Box<dyn Error>> {
 }
 
 #[cfg(any(target_os = "macos", target_os = "linux"))]
-pub fn apply_bee_workaround(version: &str, arch: Option<String>) -> Result<(), Box<dyn Error>> {
+pub fn apply_bee_workaround(version: &UnityVersion, arch: Option<String>) -> Result<(), Box<dyn Error>> {
     let editor_path = editors::get_installed_editor_path(version, arch)?;
     if let Some(editor_path) = editor_path {
         for entry in WalkDir::new(editor_path)
@@ -27,7 +29,7 @@ pub fn apply_bee_workaround(version: &str, arch: Option<String>) -> Result<(), B
             if real_bee_path.exists() {
                 continue;
             }
-            println!("Applying bee workaround to '{}'", entry_path.display());
+            progress::report(ProgressEvent::WorkaroundApplied { path: entry_path.to_path_buf() });
             fs::rename(entry_path, &real_bee_path)?;
             fs::write(entry_path, "\
         #! /bin/bash